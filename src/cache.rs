@@ -0,0 +1,393 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs::{read_to_string, write},
+    io,
+    path::Path,
+};
+
+use crate::{
+    ir::DocKind,
+    symbols::{FileSymbols, Symbol},
+};
+
+// cache file written in output directory.
+const CACHE_FILE: &str = ".gx_md_cache";
+// bump it on every change of cache format or of rendered output.
+const CACHE_VERSION: u32 = 8;
+
+// 64 bit fnv-1a, stable between run and platform unlike std hasher.
+pub(crate) fn hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |h, b| {
+        (h ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+// state of one source header at the end of last run.
+#[derive(Clone)]
+pub(crate) struct CacheEntry {
+    pub(crate) path: String,
+    pub(crate) url: String,
+    pub(crate) src: u64,
+    pub(crate) out_page: String,
+    pub(crate) page: u64,
+    pub(crate) out_side: String,
+    pub(crate) side: u64,
+    // hash of include graph shown on page.
+    pub(crate) graph: u64,
+    pub(crate) symbols: FileSymbols,
+}
+
+impl CacheEntry {
+    // source and its include graph did not change and its output is still there.
+    pub(crate) fn is_fresh(&self, src: u64, graph: u64) -> bool {
+        self.src == src
+            && self.graph == graph
+            && Path::new(&self.out_page).exists()
+            && (self.out_side.is_empty() || Path::new(&self.out_side).exists())
+    }
+}
+
+pub(crate) struct Cache {
+    config: u64,
+    files: BTreeMap<String, CacheEntry>,
+}
+
+impl Cache {
+    pub(crate) fn new(config: u64) -> Self {
+        Cache {
+            config,
+            files: BTreeMap::new(),
+        }
+    }
+
+    // empty cache when file is missing, unreadable or made with other config.
+    pub(crate) fn load(out: &str, config: u64) -> Self {
+        let mut cache = Cache::new(config);
+        let text = match read_to_string(Path::new(out).join(CACHE_FILE)) {
+            Ok(x) => x,
+            Err(_) => return cache,
+        };
+        let mut lines = text.lines();
+        let header = format!("gx_md cache {} {:016x}", CACHE_VERSION, config);
+        if lines.next() != Some(header.as_str()) {
+            return cache;
+        }
+
+        let mut current: Option<(String, CacheEntry)> = None;
+        for line in lines {
+            let mut parts = line.split('\t');
+            match parts.next() {
+                Some("file") => {
+                    let entry = parse_entry(&mut parts);
+                    match entry {
+                        Some(x) => {
+                            if let Some((k, v)) = current.replace(x) {
+                                cache.files.insert(k, v);
+                            }
+                        }
+                        None => return Cache::new(config),
+                    }
+                }
+                Some("sym") => match (current.as_mut(), parse_symbol(&mut parts)) {
+                    (Some((_, e)), Some(x)) => e.symbols.push(x),
+                    _ => return Cache::new(config),
+                },
+                _ => return Cache::new(config),
+            }
+        }
+        if let Some((k, v)) = current {
+            cache.files.insert(k, v);
+        }
+        cache
+    }
+
+    pub(crate) fn save(&self, out: &str) -> io::Result<()> {
+        let mut text = format!("gx_md cache {} {:016x}\n", CACHE_VERSION, self.config);
+        for (src, e) in &self.files {
+            text.push_str(&format!(
+                "file\t{}\t{}\t{}\t{:016x}\t{}\t{:016x}\t{}\t{:016x}\t{:016x}\n",
+                src, e.path, e.url, e.src, e.out_page, e.page, e.out_side, e.side, e.graph
+            ));
+            for (names, s) in &e.symbols {
+                text.push_str(&format!(
+                    "sym\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+                    s.kind.into_str(),
+                    s.name,
+                    s.page,
+                    s.anchor,
+                    s.file,
+                    s.summary,
+                    s.uses.join(","),
+                    // `!` keep deprecated without reason apart from not deprecated
+                    s.deprecated
+                        .as_ref()
+                        .map(|x| format!("!{}", x))
+                        .unwrap_or_default(),
+                    names.join("\t")
+                ));
+            }
+        }
+        write(Path::new(out).join(CACHE_FILE), text)
+    }
+
+    pub(crate) fn get(&self, src: &str) -> Option<&CacheEntry> {
+        self.files.get(src)
+    }
+
+    pub(crate) fn insert(&mut self, src: &str, entry: CacheEntry) {
+        self.files.insert(String::from(src), entry);
+    }
+
+    // names whose definitions are not the same as in last run, page linking them must be
+    // rendered again even when its own source did not change. types used by changed
+    // definitions are included as their back-links can change too.
+    pub(crate) fn changed_names(&self, symbols: &[FileSymbols]) -> HashSet<String> {
+        let old = definitions(self.files.values().map(|e| &e.symbols));
+        let new = definitions(symbols.iter());
+        let changed = old
+            .keys()
+            .chain(new.keys())
+            .filter(|n| old.get(*n) != new.get(*n))
+            .collect::<HashSet<&&str>>();
+        let uses = changed
+            .iter()
+            .flat_map(|n| old.get(**n).into_iter().chain(new.get(**n)).flatten())
+            .flat_map(|s| s.uses.iter().map(|x| x.as_str()));
+        changed
+            .iter()
+            .map(|n| **n)
+            .chain(uses)
+            .map(String::from)
+            .collect()
+    }
+}
+
+fn definitions<'a, I>(files: I) -> HashMap<&'a str, Vec<&'a Symbol>>
+where
+    I: Iterator<Item = &'a FileSymbols>,
+{
+    let mut defs: HashMap<&str, Vec<&Symbol>> = HashMap::new();
+    for (names, symbol) in files.flatten() {
+        for name in names {
+            defs.entry(name).or_default().push(symbol);
+        }
+    }
+    defs
+}
+
+fn parse_hash(str: Option<&str>) -> Option<u64> {
+    u64::from_str_radix(str?, 16).ok()
+}
+
+fn parse_entry<'a, I>(parts: &mut I) -> Option<(String, CacheEntry)>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let src = String::from(parts.next()?);
+    let entry = CacheEntry {
+        path: String::from(parts.next()?),
+        url: String::from(parts.next()?),
+        src: parse_hash(parts.next())?,
+        out_page: String::from(parts.next()?),
+        page: parse_hash(parts.next())?,
+        out_side: String::from(parts.next()?),
+        side: parse_hash(parts.next())?,
+        graph: parse_hash(parts.next())?,
+        symbols: vec![],
+    };
+    Some((src, entry))
+}
+
+fn parse_symbol<'a, I>(parts: &mut I) -> Option<(Vec<String>, Symbol)>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let symbol = Symbol {
+        kind: DocKind::from_str(parts.next()?)?,
+        name: String::from(parts.next()?),
+        page: String::from(parts.next()?),
+        anchor: String::from(parts.next()?),
+        file: String::from(parts.next()?),
+        summary: String::from(parts.next()?),
+        uses: parts
+            .next()?
+            .split(',')
+            .filter(|x| !x.is_empty())
+            .map(String::from)
+            .collect(),
+        deprecated: parts.next()?.strip_prefix('!').map(String::from),
+    };
+    Some((parts.map(String::from).collect(), symbol))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env::temp_dir,
+        fs::{create_dir_all, remove_dir_all},
+        path::PathBuf,
+    };
+
+    use super::*;
+
+    // fresh directory of one test, removed when test ends.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = temp_dir().join(format!("gx_md_cache_{}_{}", name, std::process::id()));
+            let _ = remove_dir_all(&dir);
+            create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+
+        fn path(&self) -> &str {
+            self.0.to_str().unwrap()
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = remove_dir_all(&self.0);
+        }
+    }
+
+    fn symbol(name: &str, uses: &[&str], deprecated: Option<&str>) -> (Vec<String>, Symbol) {
+        let symbol = Symbol {
+            name: String::from(name),
+            kind: DocKind::Function,
+            page: String::from("T"),
+            anchor: String::from(name),
+            file: String::from("/t.h"),
+            summary: String::from("does it."),
+            uses: uses.iter().map(|x| x.to_string()).collect(),
+            deprecated: deprecated.map(String::from),
+        };
+        (vec![String::from(name)], symbol)
+    }
+
+    fn entry(dir: &TempDir, symbols: FileSymbols) -> CacheEntry {
+        let out_page = format!("{}/T.md", dir.path());
+        write(&out_page, "page").unwrap();
+        CacheEntry {
+            path: String::from("/t.h"),
+            url: String::from("T"),
+            src: 1,
+            out_page,
+            page: 2,
+            out_side: String::new(),
+            side: 3,
+            graph: 4,
+            symbols,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let dir = TempDir::new("round_trip");
+        let symbols = vec![
+            symbol("open", &["struct Obj", "size_t"], None),
+            symbol("close", &[], Some("")),
+            symbol("reset", &[], Some("use close")),
+        ];
+        let mut cache = Cache::new(42);
+        cache.insert("src/t.h", entry(&dir, symbols.clone()));
+        cache.save(dir.path()).unwrap();
+
+        let loaded = Cache::load(dir.path(), 42);
+        let e = loaded.get("src/t.h").unwrap();
+        assert_eq!((e.path.as_str(), e.url.as_str()), ("/t.h", "T"));
+        assert_eq!((e.src, e.page, e.side, e.graph), (1, 2, 3, 4));
+        assert_eq!(e.out_side, "");
+        assert!(e.symbols == symbols);
+        assert!(e.is_fresh(1, 4));
+        assert!(!e.is_fresh(5, 4));
+        assert!(!e.is_fresh(1, 5));
+    }
+
+    #[test]
+    fn other_version_or_config() {
+        let dir = TempDir::new("version");
+        let mut cache = Cache::new(42);
+        cache.insert("src/t.h", entry(&dir, vec![symbol("open", &[], None)]));
+        cache.save(dir.path()).unwrap();
+
+        assert!(Cache::load(dir.path(), 43).get("src/t.h").is_none());
+
+        let file = Path::new(dir.path()).join(CACHE_FILE);
+        let text = read_to_string(&file).unwrap();
+        let old = text.replacen(
+            &format!("cache {} ", CACHE_VERSION),
+            &format!("cache {} ", CACHE_VERSION - 1),
+            1,
+        );
+        write(&file, old).unwrap();
+        assert!(Cache::load(dir.path(), 42).get("src/t.h").is_none());
+    }
+
+    #[test]
+    fn corrupted_file() {
+        let dir = TempDir::new("corrupted");
+        let mut cache = Cache::new(42);
+        cache.insert("src/t.h", entry(&dir, vec![symbol("open", &[], None)]));
+        cache.save(dir.path()).unwrap();
+        let file = Path::new(dir.path()).join(CACHE_FILE);
+        let text = read_to_string(&file).unwrap();
+        let header = text.lines().next().unwrap().to_string();
+
+        let broken = [
+            // file line cut in the middle
+            text[..text.find("\tT\t").unwrap()].to_string(),
+            // hash is not hex
+            text.replacen("\t0000000000000001\t", "\tnot-a-hash\t", 1),
+            // symbol of unknown kind
+            text.replacen("sym\tfunction", "sym\tmacro", 1),
+            // symbol before any file
+            format!(
+                "{}\nsym\tfunction\topen\tT\topen\t/t.h\t\t\t\topen\n",
+                header
+            ),
+            // unknown line
+            format!("{}\ngarbage\n", text),
+        ];
+        for x in broken {
+            write(&file, &x).unwrap();
+            assert!(
+                Cache::load(dir.path(), 42).get("src/t.h").is_none(),
+                "{}",
+                x
+            );
+        }
+        assert!(Cache::load(&format!("{}/missing", dir.path()), 42)
+            .get("src/t.h")
+            .is_none());
+    }
+
+    #[test]
+    fn changed_names_with_uses() {
+        let dir = TempDir::new("changed");
+        let mut cache = Cache::new(42);
+        let old = vec![
+            symbol("open", &["Obj"], None),
+            symbol("close", &["Handle"], None),
+            symbol("stay", &["Other"], None),
+        ];
+        cache.insert("src/t.h", entry(&dir, old));
+
+        // close is deprecated, open removed, new added
+        let new = vec![vec![
+            symbol("close", &["Handle"], Some("")),
+            symbol("stay", &["Other"], None),
+            symbol("new", &[], None),
+        ]];
+        let mut changed = cache
+            .changed_names(&new)
+            .into_iter()
+            .collect::<Vec<String>>();
+        changed.sort();
+        assert_eq!(changed, ["Handle", "Obj", "close", "new", "open"]);
+        assert!(cache
+            .changed_names(&[cache.get("src/t.h").unwrap().symbols.clone()])
+            .is_empty());
+    }
+}
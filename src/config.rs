@@ -0,0 +1,495 @@
+use crate::{lint::LintLevel, worker::default_jobs};
+
+enum Command {
+    Src,
+    OutDir,
+    Home,
+    Impl,
+    Private,
+    IncludePrivate,
+    MinCoverage,
+    Json,
+    Lint,
+    Format,
+    Jobs,
+    Watch,
+    Sidebar,
+    TemplateDir,
+    Fields,
+    IncludeGraph,
+    ClassDiagram,
+    Name,
+}
+
+impl Command {
+    #[allow(clippy::wrong_self_convention)]
+    pub(crate) fn into_str(&self) -> &str {
+        match self {
+            Self::Src => "-src",
+            Self::OutDir => "-o",
+            Self::Home => "-h",
+            Self::Impl => "-impl",
+            Self::Private => "-private",
+            Self::IncludePrivate => "-include-private",
+            Self::MinCoverage => "-min-coverage",
+            Self::Json => "-json",
+            Self::Lint => "-lint",
+            Self::Format => "-format",
+            Self::Jobs => "-j",
+            Self::Watch => "-watch",
+            Self::Sidebar => "-sidebar",
+            Self::TemplateDir => "-template-dir",
+            Self::Fields => "-fields",
+            Self::IncludeGraph => "-include-graph",
+            Self::ClassDiagram => "-class-diagram",
+            Self::Name => "gx_md",
+        }
+    }
+
+    // long form `--name` is accepted for every `-name` option.
+    fn from_str(str: &str) -> Option<Self> {
+        let str = match str.strip_prefix("--") {
+            Some(x) => format!("-{}", x),
+            None => String::from(str),
+        };
+        [
+            Self::Src,
+            Self::OutDir,
+            Self::Home,
+            Self::Impl,
+            Self::Private,
+            Self::IncludePrivate,
+            Self::MinCoverage,
+            Self::Json,
+            Self::Lint,
+            Self::Format,
+            Self::Jobs,
+            Self::Watch,
+            Self::Sidebar,
+            Self::TemplateDir,
+            Self::Fields,
+            Self::IncludeGraph,
+            Self::ClassDiagram,
+            Self::Name,
+        ]
+        .into_iter()
+        .find(|x| x.into_str() == str)
+    }
+}
+
+#[derive(PartialEq, Eq)]
+pub(crate) enum Mode {
+    Generate,
+    Check,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Format {
+    Markdown,
+    Json,
+    Html,
+    MdBook,
+    Single,
+    Man,
+}
+
+// page: sidebar of each page list its symbols.
+// global: one sidebar list every header, symbols of page move to its table of contents.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Sidebar {
+    Page,
+    Global,
+}
+
+impl Sidebar {
+    #[allow(clippy::wrong_self_convention)]
+    pub(crate) fn into_str(&self) -> &str {
+        match self {
+            Self::Page => "page",
+            Self::Global => "global",
+        }
+    }
+
+    fn from_str(str: &str) -> Option<Self> {
+        [Self::Page, Self::Global]
+            .into_iter()
+            .find(|x| x.into_str() == str)
+    }
+}
+
+// code: struct fields only in declaration, table: only in fields table, both: in both.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Fields {
+    Code,
+    Table,
+    Both,
+}
+
+impl Fields {
+    #[allow(clippy::wrong_self_convention)]
+    pub(crate) fn into_str(&self) -> &str {
+        match self {
+            Self::Code => "code",
+            Self::Table => "table",
+            Self::Both => "both",
+        }
+    }
+
+    fn from_str(str: &str) -> Option<Self> {
+        [Self::Code, Self::Table, Self::Both]
+            .into_iter()
+            .find(|x| x.into_str() == str)
+    }
+}
+
+// off: no include graph. home: mermaid graph of every header on home page and dot file.
+// pages: home graph plus graph of included and including headers on each page.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Graph {
+    Off,
+    Home,
+    Pages,
+}
+
+impl Graph {
+    #[allow(clippy::wrong_self_convention)]
+    pub(crate) fn into_str(&self) -> &str {
+        match self {
+            Self::Off => "off",
+            Self::Home => "home",
+            Self::Pages => "pages",
+        }
+    }
+
+    fn from_str(str: &str) -> Option<Self> {
+        [Self::Off, Self::Home, Self::Pages]
+            .into_iter()
+            .find(|x| x.into_str() == str)
+    }
+}
+
+// off: no class diagram. pages: diagram of structs of each header.
+// project: home page show structs of every header instead of its own.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Diagram {
+    Off,
+    Pages,
+    Project,
+}
+
+impl Diagram {
+    #[allow(clippy::wrong_self_convention)]
+    pub(crate) fn into_str(&self) -> &str {
+        match self {
+            Self::Off => "off",
+            Self::Pages => "pages",
+            Self::Project => "project",
+        }
+    }
+
+    fn from_str(str: &str) -> Option<Self> {
+        [Self::Off, Self::Pages, Self::Project]
+            .into_iter()
+            .find(|x| x.into_str() == str)
+    }
+}
+
+impl Format {
+    fn from_str(str: &str) -> Option<Self> {
+        match str {
+            "markdown" | "md" => Some(Self::Markdown),
+            "json" => Some(Self::Json),
+            "html" => Some(Self::Html),
+            "mdbook" => Some(Self::MdBook),
+            "single" => Some(Self::Single),
+            "man" => Some(Self::Man),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) const USAGE: &str =
+    "Usage: gx_md -src [source dir] -o [output directory] -h [home_src_file.h] [options]
+       gx_md check -src [source dir] [-min-coverage percent] [-json] [options]
+Options:
+    -format [format]      output format: markdown (default), json, html, mdbook,
+                          single (whole api in one markdown file) or man (section 3).
+    -j [n]                number of files processed in parallel, default to number of cpu.
+    -sidebar [kind]       page (default): one sidebar per page listing its symbols.
+                          global: one sidebar listing every header, symbols of a page
+                          are listed in its table of contents.
+    -fields [view]        struct fields shown as code, table or both (default).
+    -include-graph [kind] home (default): include graph of every header on home page
+                          and in include-graph.dot. pages: each page also show headers
+                          it includes and headers including it. off: no graph.
+    -class-diagram [kind] pages (default): mermaid class diagram of structs of each header.
+                          project: home page show structs of every header. off: none.
+    -template-dir [dir]   replace page, content, item and sidebar layout of markdown
+                          pages by `<name>.tpl` files of dir.
+    -watch                regenerate affected pages whenever a source file change.
+    -impl                 merge documentation of .c definitions into header pages.
+    -private [pattern]    treat items whose name match pattern as private, `*` match any text.
+    -include-private      render private items in Internal section instead of hiding them.
+    -min-coverage [n]     check: exit with error when documentation coverage is below n percent.
+    -json                 check: print coverage report as json.
+    -lint [level]         check: report doc tags not matching declarations as
+                          off, warn (default) or error. error level fail the check.";
+
+pub(crate) struct Config {
+    pub(crate) mode: Mode,
+    pub(crate) src: String,
+    pub(crate) out: String,
+    pub(crate) home: String,
+    pub(crate) merge_impl: bool,
+    pub(crate) private_patterns: Vec<String>,
+    pub(crate) include_private: bool,
+    pub(crate) min_coverage: Option<f64>,
+    pub(crate) json: bool,
+    pub(crate) lint: LintLevel,
+    pub(crate) format: Format,
+    pub(crate) jobs: usize,
+    pub(crate) watch: bool,
+    pub(crate) sidebar: Sidebar,
+    pub(crate) template_dir: Option<String>,
+    pub(crate) fields: Fields,
+    pub(crate) include_graph: Graph,
+    pub(crate) class_diagram: Diagram,
+}
+
+impl Config {
+    // error names the option and value that can not be used.
+    pub(crate) fn from_args(args: Vec<String>) -> Result<Self, String> {
+        let mut cfg = Config {
+            mode: Mode::Generate,
+            src: String::new(),
+            out: String::new(),
+            home: String::new(),
+            merge_impl: false,
+            private_patterns: vec![],
+            include_private: false,
+            min_coverage: None,
+            json: false,
+            lint: LintLevel::Warn,
+            format: Format::Markdown,
+            jobs: default_jobs(),
+            watch: false,
+            sidebar: Sidebar::Page,
+            template_dir: None,
+            fields: Fields::Both,
+            include_graph: Graph::Home,
+            class_diagram: Diagram::Pages,
+        };
+
+        // option waiting for its value, Name when none is
+        let mut prev: Command = Command::Name;
+        for arg in args.into_iter().skip(1) {
+            if arg.starts_with('-') && !matches!(prev, Command::Name) {
+                return Err(format!("missing value of {}", prev.into_str()));
+            }
+            let command = Command::from_str(&arg).filter(|x| !matches!(x, Command::Name));
+            if let Some(x) = command {
+                match x {
+                    Command::Impl => cfg.merge_impl = true,
+                    Command::IncludePrivate => cfg.include_private = true,
+                    Command::Json => cfg.json = true,
+                    Command::Watch => cfg.watch = true,
+                    x => prev = x,
+                }
+                continue;
+            }
+            if arg.starts_with('-') {
+                return Err(format!("unknown option {}", arg));
+            }
+            match prev {
+                Command::Src => {
+                    cfg.src = arg;
+                }
+                Command::OutDir => {
+                    cfg.out = arg;
+                }
+                Command::Home => {
+                    cfg.home = arg;
+                }
+                Command::Private => {
+                    cfg.private_patterns.push(arg);
+                }
+                Command::MinCoverage => match arg.trim_end_matches('%').parse::<f64>() {
+                    Ok(x) => cfg.min_coverage = Some(x),
+                    Err(_) => return Err(format!("invalid coverage value {}", arg)),
+                },
+                Command::Lint => match LintLevel::from_str(&arg) {
+                    Some(x) => cfg.lint = x,
+                    None => return Err(format!("invalid lint level {}", arg)),
+                },
+                Command::Format => match Format::from_str(&arg) {
+                    Some(x) => cfg.format = x,
+                    None => return Err(format!("invalid format {}", arg)),
+                },
+                Command::Sidebar => match Sidebar::from_str(&arg) {
+                    Some(x) => cfg.sidebar = x,
+                    None => return Err(format!("invalid sidebar {}", arg)),
+                },
+                Command::TemplateDir => {
+                    cfg.template_dir = Some(arg);
+                }
+                Command::Fields => match Fields::from_str(&arg) {
+                    Some(x) => cfg.fields = x,
+                    None => return Err(format!("invalid fields view {}", arg)),
+                },
+                Command::IncludeGraph => match Graph::from_str(&arg) {
+                    Some(x) => cfg.include_graph = x,
+                    None => return Err(format!("invalid include graph {}", arg)),
+                },
+                Command::ClassDiagram => match Diagram::from_str(&arg) {
+                    Some(x) => cfg.class_diagram = x,
+                    None => return Err(format!("invalid class diagram {}", arg)),
+                },
+                Command::Jobs => match arg.parse::<usize>() {
+                    Ok(x) if x > 0 => cfg.jobs = x,
+                    _ => return Err(format!("invalid number of jobs {}", arg)),
+                },
+                // only mode can stand alone
+                Command::Name => match arg.as_str() {
+                    "check" => cfg.mode = Mode::Check,
+                    _ => return Err(format!("unexpected argument {}", arg)),
+                },
+                Command::Impl | Command::IncludePrivate | Command::Json | Command::Watch => {}
+            }
+            // value is taken, next argument is an option or the mode
+            prev = Command::Name;
+        }
+        if !matches!(prev, Command::Name) {
+            return Err(format!("missing value of {}", prev.into_str()));
+        }
+        Ok(cfg)
+    }
+
+    pub(crate) fn is_valid(&self) -> bool {
+        match self.mode {
+            Mode::Generate => !self.src.is_empty() && !self.out.is_empty() && !self.home.is_empty(),
+            Mode::Check => !self.src.is_empty(),
+        }
+    }
+
+    // every option that change generated pages, part of cache key.
+    pub(crate) fn fingerprint(&self) -> String {
+        format!(
+            "{} src={} out={} home={} impl={} private={} include_private={} sidebar={} fields={} include_graph={} class_diagram={}",
+            env!("CARGO_PKG_VERSION"),
+            self.src,
+            self.out,
+            self.home,
+            self.merge_impl,
+            self.private_patterns.join(","),
+            self.include_private,
+            self.sidebar.into_str(),
+            self.fields.into_str(),
+            self.include_graph.into_str(),
+            self.class_diagram.into_str()
+        )
+    }
+
+    pub(crate) fn is_private_name(&self, name: &str) -> bool {
+        self.private_patterns
+            .iter()
+            .any(|p| wildcard_match(p.as_bytes(), name.as_bytes()))
+    }
+}
+
+// match name with pattern where `*` match any sequence of character.
+fn wildcard_match(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => (0..=name.len()).any(|i| wildcard_match(rest, &name[i..])),
+        Some((c, rest)) => match name.split_first() {
+            Some((n, name_rest)) if n == c => wildcard_match(rest, name_rest),
+            _ => false,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Config, String> {
+        let args = ["gx_md"].iter().chain(args).map(|x| x.to_string());
+        Config::from_args(args.collect())
+    }
+
+    #[test]
+    fn options_and_values() {
+        let cfg = parse(&[
+            "check",
+            "-src",
+            "s",
+            "-min-coverage",
+            "80%",
+            "-lint",
+            "error",
+        ])
+        .unwrap();
+        assert!(cfg.mode == Mode::Check);
+        assert_eq!(cfg.src, "s");
+        assert_eq!(cfg.min_coverage, Some(80.0));
+        assert!(cfg.lint == LintLevel::Error);
+        assert!(cfg.is_valid());
+
+        let cfg = parse(&["--src", "s", "-o", "o", "-h", "t.h", "-j", "2", "-impl"]).unwrap();
+        assert_eq!(cfg.jobs, 2);
+        assert!(cfg.merge_impl);
+        assert!(cfg.is_valid());
+    }
+
+    #[test]
+    fn invalid_value_is_error() {
+        for args in [
+            ["-min-coverage", "8o"],
+            ["-lint", "loud"],
+            ["-format", "pdf"],
+            ["-j", "0"],
+            ["-fields", "grid"],
+            ["-sidebar", "left"],
+            ["-include-graph", "all"],
+            ["-class-diagram", "uml"],
+        ] {
+            let err = parse(&args).err().unwrap();
+            assert!(err.ends_with(args[1]), "{}", err);
+        }
+    }
+
+    #[test]
+    fn unknown_option_is_error() {
+        let err = parse(&["-src", "s", "-colour", "red"]).err().unwrap();
+        assert_eq!(err, "unknown option -colour");
+    }
+
+    #[test]
+    fn stray_argument_is_error() {
+        let err = parse(&["-src", "s", "docs"]).err().unwrap();
+        assert_eq!(err, "unexpected argument docs");
+        let err = parse(&["-impl", "yes"]).err().unwrap();
+        assert_eq!(err, "unexpected argument yes");
+        // program path is not an argument
+        let args = ["./target/gx_md", "check", "-src", "s"].map(String::from);
+        assert!(Config::from_args(args.to_vec()).is_ok());
+    }
+
+    #[test]
+    fn option_without_value_is_error() {
+        let err = parse(&["-src", "-impl", "-o", "o"]).err().unwrap();
+        assert_eq!(err, "missing value of -src");
+        let err = parse(&["-o", "-x"]).err().unwrap();
+        assert_eq!(err, "missing value of -o");
+        let err = parse(&["-src", "s", "-o"]).err().unwrap();
+        assert_eq!(err, "missing value of -o");
+    }
+
+    #[test]
+    fn private_pattern() {
+        let cfg = parse(&["-private", "_*", "-private", "*_impl"]).unwrap();
+        assert!(cfg.is_private_name("_reset"));
+        assert!(cfg.is_private_name("buf_impl"));
+        assert!(!cfg.is_private_name("buf_open"));
+    }
+}
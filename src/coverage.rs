@@ -0,0 +1,331 @@
+use crate::{
+    json::Json,
+    page::{content::Content, view::FieldView},
+    utils::{AnchorMd, IntoMd, TitleMd},
+};
+
+// item without description.
+#[derive(Clone)]
+pub(crate) struct Undocumented {
+    name: String,
+    kind: String,
+}
+
+pub(crate) struct FileCoverage {
+    file: String,
+    total: usize,
+    undocumented: Vec<Undocumented>,
+}
+
+impl FileCoverage {
+    pub(crate) fn new(file: &str, content: &Content) -> Self {
+        let mut total = 0;
+        let mut undocumented = vec![];
+        collect(
+            &content.get_objects(),
+            "object",
+            &mut total,
+            &mut undocumented,
+        );
+        collect(
+            &content.get_func(),
+            "function",
+            &mut total,
+            &mut undocumented,
+        );
+        collect(
+            &content.get_include(),
+            "include",
+            &mut total,
+            &mut undocumented,
+        );
+        FileCoverage {
+            file: String::from(file),
+            total,
+            undocumented,
+        }
+    }
+
+    fn documented(&self) -> usize {
+        self.total - self.undocumented.len()
+    }
+}
+
+fn collect<T>(items: &[FieldView<T>], kind: &str, total: &mut usize, out: &mut Vec<Undocumented>)
+where
+    T: IntoMd + TitleMd + AnchorMd + Clone,
+{
+    items.iter().filter(|x| !x.is_private()).for_each(|x| {
+        *total += 1;
+        let documented = match x.get_desc() {
+            Some(d) => !d.trim().is_empty(),
+            None => false,
+        };
+        if !documented {
+            out.push(Undocumented {
+                name: x.get_field().create_title(),
+                kind: String::from(kind),
+            });
+        }
+    });
+}
+
+// percentage rounded down to one decimal, the same value is shown and compared
+// to minimum so 99.95% is never shown as 100%.
+fn percent(documented: usize, total: usize) -> f64 {
+    match total {
+        0 => 100.0,
+        _ => (documented * 1000 / total) as f64 / 10.0,
+    }
+}
+
+pub(crate) struct Coverage {
+    files: Vec<FileCoverage>,
+}
+
+impl Coverage {
+    pub(crate) fn new() -> Self {
+        Coverage { files: vec![] }
+    }
+
+    pub(crate) fn add(&mut self, file: FileCoverage) {
+        self.files.push(file);
+    }
+
+    pub(crate) fn percent(&self) -> f64 {
+        let files = &self.files;
+        let total = files.iter().map(|x| x.total).sum();
+        let documented = files.iter().map(|x| x.documented()).sum();
+        percent(documented, total)
+    }
+
+    // coverage reach minimum, always true without minimum.
+    pub(crate) fn passed(&self, min_coverage: Option<f64>) -> bool {
+        match min_coverage {
+            Some(x) => self.percent() >= x,
+            None => true,
+        }
+    }
+
+    pub(crate) fn render_table(&self) -> String {
+        let files = &self.files;
+        let width = files
+            .iter()
+            .map(|x| x.file.len())
+            .max()
+            .unwrap_or(0)
+            .max("Total".len());
+
+        let mut lines = vec![format!(
+            "{:<width$}  {:>10}  {:>5}  {:>8}",
+            "File",
+            "Documented",
+            "Total",
+            "Coverage",
+            width = width
+        )];
+        let mut total = 0;
+        let mut documented = 0;
+        files.iter().for_each(|f| {
+            total += f.total;
+            documented += f.documented();
+            lines.push(format!(
+                "{:<width$}  {:>10}  {:>5}  {:>7.1}%",
+                f.file,
+                f.documented(),
+                f.total,
+                percent(f.documented(), f.total),
+                width = width
+            ));
+            f.undocumented.iter().for_each(|u| {
+                lines.push(format!("    undocumented {}: {}", u.kind, u.name));
+            });
+        });
+        lines.push(format!(
+            "{:<width$}  {:>10}  {:>5}  {:>7.1}%",
+            "Total",
+            documented,
+            total,
+            percent(documented, total),
+            width = width
+        ));
+        lines.join("\n")
+    }
+
+    pub(crate) fn render_json(&self, min_coverage: Option<f64>) -> String {
+        let files = &self.files;
+        let files = files
+            .iter()
+            .map(|f| {
+                let undocumented = f
+                    .undocumented
+                    .iter()
+                    .map(|u| {
+                        Json::obj(vec![
+                            ("name", Json::str(&u.name)),
+                            ("kind", Json::str(&u.kind)),
+                        ])
+                    })
+                    .collect::<Vec<Json>>();
+                Json::obj(vec![
+                    ("file", Json::str(&f.file)),
+                    ("documented", Json::Num(f.documented() as f64)),
+                    ("total", Json::Num(f.total as f64)),
+                    ("coverage", Json::Num(percent(f.documented(), f.total))),
+                    ("undocumented", Json::Arr(undocumented)),
+                ])
+            })
+            .collect::<Vec<Json>>();
+
+        let min = match min_coverage {
+            Some(x) => Json::Num(x),
+            None => Json::Null,
+        };
+
+        Json::obj(vec![
+            ("coverage", Json::Num(self.percent())),
+            ("min_coverage", min),
+            ("passed", Json::Bool(self.passed(min_coverage))),
+            ("files", Json::Arr(files)),
+        ])
+        .render()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufRead;
+
+    use super::*;
+    use crate::{config::Config, parser::str_parser, utils::c_impl::CImplDocs};
+
+    fn file(name: &str, text: &str) -> FileCoverage {
+        let args = ["gx_md", "-src", "src", "-private", "_*"];
+        let cfg = Config::from_args(args.iter().map(|x| x.to_string()).collect()).unwrap();
+        let content = str_parser(text.as_bytes().lines(), &cfg, &CImplDocs::new());
+        FileCoverage::new(name, &content)
+    }
+
+    fn coverage(files: Vec<FileCoverage>) -> Coverage {
+        let mut coverage = Coverage::new();
+        files.into_iter().for_each(|f| coverage.add(f));
+        coverage
+    }
+
+    const TEXT: &str = concat!(
+        "#include \"a.h\"\n",
+        "\n",
+        "//! a point\n",
+        "struct Pt {\n",
+        "    int x;\n",
+        "};\n",
+        "\n",
+        "int size(void);\n",
+        "\n",
+        "//!\n",
+        "int blank(void);\n",
+        "\n",
+        "//! hidden by pattern\n",
+        "void _reset(void);\n",
+        "\n",
+        "//! @private\n",
+        "void hidden(void);\n",
+    );
+
+    #[test]
+    fn documented_and_undocumented() {
+        let f = file("/t.h", TEXT);
+        // private items are not counted
+        assert_eq!(f.total, 4);
+        assert_eq!(f.documented(), 1);
+        let names = f
+            .undocumented
+            .iter()
+            .map(|u| format!("{} {}", u.kind, u.name))
+            .collect::<Vec<String>>();
+        assert_eq!(names, ["function size", "function blank", "include a.h"]);
+    }
+
+    #[test]
+    fn rounding() {
+        assert_eq!(percent(2, 3), 66.6);
+        assert_eq!(percent(1999, 2000), 99.9);
+        assert_eq!(percent(3, 3), 100.0);
+        // nothing to document is fully documented
+        assert_eq!(percent(0, 0), 100.0);
+
+        let c = coverage(vec![
+            file("/t.h", TEXT),
+            file("/u.h", "//! a\nint a(void);\n"),
+        ]);
+        assert_eq!(c.percent(), 40.0);
+        let table = c.render_table();
+        assert!(
+            table.contains("/t.h            1      4     25.0%"),
+            "{}",
+            table
+        );
+        assert!(
+            table.contains("    undocumented function: size"),
+            "{}",
+            table
+        );
+        assert!(
+            table.ends_with("Total           2      5     40.0%"),
+            "{}",
+            table
+        );
+    }
+
+    #[test]
+    fn minimum() {
+        let c = coverage(vec![file("/t.h", TEXT)]);
+        assert!(c.passed(None));
+        assert!(c.passed(Some(25.0)));
+        assert!(!c.passed(Some(25.1)));
+        assert!(coverage(vec![]).passed(Some(100.0)));
+    }
+
+    #[test]
+    fn json_report() {
+        let c = coverage(vec![file("/t.h", TEXT)]);
+        assert_eq!(
+            c.render_json(Some(50.0)),
+            concat!(
+                "{\n",
+                "  \"coverage\": 25,\n",
+                "  \"min_coverage\": 50,\n",
+                "  \"passed\": false,\n",
+                "  \"files\": [\n",
+                "    {\n",
+                "      \"file\": \"/t.h\",\n",
+                "      \"documented\": 1,\n",
+                "      \"total\": 4,\n",
+                "      \"coverage\": 25,\n",
+                "      \"undocumented\": [\n",
+                "        {\n",
+                "          \"name\": \"size\",\n",
+                "          \"kind\": \"function\"\n",
+                "        },\n",
+                "        {\n",
+                "          \"name\": \"blank\",\n",
+                "          \"kind\": \"function\"\n",
+                "        },\n",
+                "        {\n",
+                "          \"name\": \"a.h\",\n",
+                "          \"kind\": \"include\"\n",
+                "        }\n",
+                "      ]\n",
+                "    }\n",
+                "  ]\n",
+                "}"
+            )
+        );
+        let json = c.render_json(None);
+        assert!(
+            json.contains("\"min_coverage\": null,\n  \"passed\": true"),
+            "{}",
+            json
+        );
+    }
+}
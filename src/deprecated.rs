@@ -0,0 +1,161 @@
+use crate::{
+    index::symbol_file,
+    symbols::{FileSymbols, Symbol},
+};
+
+// page listing every deprecated symbol of the project.
+pub(crate) const DEPRECATED_PAGE: &str = "Deprecated";
+
+// name suggested by reason: first code span, else word after `use`.
+// e.g. `use gx_open2 instead` or `replaced by [`gx_open2`]`.
+pub(crate) fn replacement(reason: &str) -> Option<String> {
+    let name = match reason.split('`').nth(1) {
+        Some(x) => x.to_string(),
+        None => {
+            let mut words = reason.split_whitespace();
+            words.find(|w| w.eq_ignore_ascii_case("use"))?;
+            words.next()?.to_string()
+        }
+    };
+    let name = name.trim_end_matches(|c: char| c.is_ascii_punctuation() && c != '_');
+    match name.is_empty() {
+        true => None,
+        false => Some(String::from(name)),
+    }
+}
+
+// deprecated symbol with reason and replacement, symbol of replacement when it is
+// documented.
+pub(crate) type Entry<'a> = (&'a Symbol, Option<(String, Option<&'a Symbol>)>);
+
+// deprecated symbols of every file sorted by name.
+pub(crate) fn entries<'a>(files: &[&'a FileSymbols]) -> Vec<Entry<'a>> {
+    let find = |name: &str| {
+        files
+            .iter()
+            .copied()
+            .flatten()
+            .find(|(names, _)| names.iter().any(|n| n == name))
+            .map(|(_, s)| s)
+    };
+    let mut list = files
+        .iter()
+        .copied()
+        .flatten()
+        .filter_map(|(_, s)| {
+            let reason = s.deprecated.as_ref()?;
+            let replacement = replacement(reason).map(|x| {
+                let symbol = find(&x);
+                (x, symbol)
+            });
+            Some((s, replacement))
+        })
+        .collect::<Vec<Entry>>();
+    list.sort_by(|(a, _), (b, _)| a.name.cmp(&b.name).then(a.file.cmp(&b.file)));
+    list
+}
+
+// markdown table of deprecated symbols. url give link of symbol seen from the page.
+pub(crate) fn deprecated_md<F>(files: &[&FileSymbols], url: F) -> String
+where
+    F: Fn(&Symbol) -> String,
+{
+    let cell = |x: &str| x.replace('|', "\\|");
+    let entries = entries(files);
+    let mut lines = vec![String::from("# Deprecated"), String::new()];
+    if entries.is_empty() {
+        lines.push(String::from("*No deprecated symbols.*"));
+        return lines.join("\n") + "\n";
+    }
+    lines.push(String::from("| Symbol | Header | Reason | Replacement |"));
+    lines.push(String::from("| --- | --- | --- | --- |"));
+    for (s, replacement) in entries {
+        let replacement = match replacement {
+            Some((x, Some(r))) => format!("[`{}`]({})", x, url(r)),
+            Some((x, None)) => format!("`{}`", x),
+            None => String::new(),
+        };
+        lines.push(format!(
+            "| [`{}`]({}) | `{}` | {} | {} |",
+            s.name,
+            url(s),
+            symbol_file(s),
+            cell(s.deprecated.as_deref().unwrap_or_default()),
+            cell(&replacement)
+        ));
+    }
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::DocKind;
+
+    fn symbol(name: &str, file: &str, deprecated: Option<&str>) -> (Vec<String>, Symbol) {
+        let symbol = Symbol {
+            name: String::from(name),
+            kind: DocKind::Function,
+            page: String::from("T"),
+            anchor: String::from(name),
+            file: String::from(file),
+            summary: String::new(),
+            uses: vec![],
+            deprecated: deprecated.map(String::from),
+        };
+        (vec![String::from(name)], symbol)
+    }
+
+    #[test]
+    fn replacement_of_reason() {
+        assert_eq!(
+            replacement("use `gx_open2` instead").as_deref(),
+            Some("gx_open2")
+        );
+        assert_eq!(
+            replacement("replaced by [`gx_open2`]").as_deref(),
+            Some("gx_open2")
+        );
+        assert_eq!(replacement("Use gx_open2.").as_deref(), Some("gx_open2"));
+        assert_eq!(
+            replacement("use gx_free_all_").as_deref(),
+            Some("gx_free_all_")
+        );
+        assert_eq!(replacement("no longer needed"), None);
+        assert_eq!(replacement("use"), None);
+        assert_eq!(replacement(""), None);
+    }
+
+    #[test]
+    fn table_of_deprecated() {
+        let a = vec![
+            symbol("open", "/a.h", Some("use `open2` | faster")),
+            symbol("open2", "/a.h", None),
+        ];
+        let b = vec![
+            symbol("close", "/sub/b.h", Some("")),
+            symbol("free", "/sub/b.h", Some("use release")),
+        ];
+        let md = deprecated_md(&[&a, &b], |s| format!("{}#{}", s.page, s.anchor));
+        let lines = md.lines().collect::<Vec<&str>>();
+        assert_eq!(
+            lines[2..],
+            [
+                "| Symbol | Header | Reason | Replacement |",
+                "| --- | --- | --- | --- |",
+                "| [`close`](T#close) | `sub/b.h` |  |  |",
+                "| [`free`](T#free) | `sub/b.h` | use release | `release` |",
+                "| [`open`](T#open) | `a.h` | use `open2` \\| faster | [`open2`](T#open2) |",
+            ]
+        );
+    }
+
+    #[test]
+    fn nothing_deprecated() {
+        let a = vec![symbol("open", "/a.h", None)];
+        assert_eq!(
+            deprecated_md(&[&a], |_| String::new()),
+            "# Deprecated\n\n*No deprecated symbols.*\n"
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+
+use crate::{
+    symbols::type_names,
+    utils::{
+        c_object::{c_variable_field::CVariableField, CObject, CObjectType},
+        IntoMd,
+    },
+};
+
+// class id of mermaid can only hold letters, digits and underscore.
+fn class_id(name: &str) -> String {
+    name.chars()
+        .map(|c| match c.is_ascii_alphanumeric() || c == '_' {
+            true => c,
+            false => '_',
+        })
+        .collect()
+}
+
+// mermaid text can not hold quote.
+fn text(str: &str) -> String {
+    str.replace('"', "'")
+}
+
+// field as mermaid member, function pointer is a method `name(params) return`.
+fn member(field: &CVariableField) -> String {
+    let unit = field.get_unit();
+    match unit.split_once("(*)") {
+        Some((ret, params)) => format!("{}{} {}", field.get_name(), params.trim(), ret.trim()),
+        None => field.into_md().trim_end_matches(';').to_string(),
+    }
+}
+
+struct Class {
+    id: String,
+    title: String,
+    aliases: Vec<String>,
+    fields: Vec<CVariableField>,
+}
+
+// classDiagram of structs and unions with their fields. typedef alias is shown in
+// label of its struct. field whose type is other struct is an edge, composition when
+// held by value and aggregation when held by pointer. external give title of struct
+// documented in other header, None for type that is not a documented struct.
+// None when there is no struct to show.
+pub(crate) fn class_diagram<F>(objects: &[CObject], external: F) -> Option<String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut classes: Vec<Class> = vec![];
+    let mut names: HashMap<String, usize> = HashMap::new();
+    for obj in objects {
+        let keyword = match obj.get_obj_type() {
+            CObjectType::Struct => "struct",
+            CObjectType::Union => "union",
+            CObjectType::Alias | CObjectType::Unknown => continue,
+        };
+        let name = obj.get_name();
+        // the same struct declared in many headers is one class
+        let i = match classes.iter().position(|c| c.id == class_id(&name)) {
+            Some(x) => x,
+            None => {
+                classes.push(Class {
+                    id: class_id(&name),
+                    title: format!("{} {}", keyword, name),
+                    aliases: vec![],
+                    fields: obj.get_fields(),
+                });
+                classes.len() - 1
+            }
+        };
+        names.insert(name.clone(), i);
+        names.insert(format!("{} {}", keyword, name), i);
+        if let Some(x) = obj.get_alias().filter(|x| *x != name) {
+            if !classes[i].aliases.contains(&x) {
+                classes[i].aliases.push(x.clone());
+            }
+            names.insert(x, i);
+        }
+    }
+    if classes.is_empty() {
+        return None;
+    }
+    // `typedef struct name alias;` label struct of this header
+    for obj in objects {
+        if let (CObjectType::Alias, Some(alias)) = (obj.get_obj_type(), obj.get_alias()) {
+            if let Some(i) = names.get(&obj.get_name()).copied() {
+                if !classes[i].aliases.contains(&alias) {
+                    classes[i].aliases.push(alias.clone());
+                }
+                names.insert(alias, i);
+            }
+        }
+    }
+
+    let local = classes.len();
+    let mut edges = vec![];
+    for i in 0..local {
+        for f in classes[i].fields.clone() {
+            let unit = f.get_unit();
+            // function pointer only point to code
+            if unit.contains("(*)") {
+                continue;
+            }
+            let target = type_names(&unit).into_iter().find_map(|n| {
+                if let Some(x) = names.get(&n) {
+                    return Some(*x);
+                }
+                let title = external(&n)?;
+                let id = class_id(title.rsplit(' ').next().unwrap_or_default());
+                let x = match classes[local..].iter().position(|c| c.id == id) {
+                    Some(x) => local + x,
+                    None => {
+                        classes.push(Class {
+                            id,
+                            title,
+                            aliases: vec![],
+                            fields: vec![],
+                        });
+                        classes.len() - 1
+                    }
+                };
+                names.insert(n, x);
+                Some(x)
+            });
+            if let Some(t) = target {
+                let arrow = match unit.contains('*') {
+                    true => "o--",
+                    false => "*--",
+                };
+                edges.push(format!(
+                    "    {} {} {} : {}",
+                    classes[i].id,
+                    arrow,
+                    classes[t].id,
+                    text(&f.get_name())
+                ));
+            }
+        }
+    }
+
+    let mut lines = vec![String::from("```mermaid"), String::from("classDiagram")];
+    for c in &classes {
+        let label = match c.aliases.is_empty() {
+            true => c.title.clone(),
+            false => format!("{} ({})", c.title, c.aliases.join(", ")),
+        };
+        lines.push(format!("    class {}[\"{}\"]", c.id, text(&label)));
+        for f in &c.fields {
+            lines.push(format!("    {} : {}", c.id, text(&member(f))));
+        }
+    }
+    lines.extend(edges);
+    lines.push(String::from("```"));
+    Some(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufRead;
+
+    use super::*;
+    use crate::{config::Config, parser::str_parser, utils::c_impl::CImplDocs};
+
+    fn objects(text: &str) -> Vec<CObject> {
+        let args = ["gx_md", "-src", "src", "-o", "out", "-h", "t.h"];
+        let cfg = Config::from_args(args.iter().map(|x| x.to_string()).collect()).unwrap();
+        str_parser(text.as_bytes().lines(), &cfg, &CImplDocs::new())
+            .get_objects()
+            .iter()
+            .map(|x| x.get_field())
+            .collect()
+    }
+
+    #[test]
+    fn classes_and_edges() {
+        let text = "struct Node {\n    int value;\n    struct Node *next;\n    struct Item item;\n    void (*free)(void *p);\n};\ntypedef struct Node Node_t;\n\nstruct Item {\n    char name[8];\n    struct Ext *ext;\n};\n";
+        let external = |n: &str| (n == "struct Ext").then(|| String::from("struct Ext"));
+        let diagram = class_diagram(&objects(text), external).unwrap();
+        let lines = diagram.lines().collect::<Vec<&str>>();
+        assert_eq!(lines[..2], ["```mermaid", "classDiagram"]);
+        assert!(lines.contains(&"    class Node[\"struct Node (Node_t)\"]"));
+        assert!(lines.contains(&"    Node : free(void *p) void"));
+        assert!(lines.contains(&"    class Ext[\"struct Ext\"]"));
+        // pointer is aggregation, value is composition, function pointer is no edge
+        assert!(lines.contains(&"    Node o-- Node : next"));
+        assert!(lines.contains(&"    Node *-- Item : item"));
+        assert!(lines.contains(&"    Item o-- Ext : ext"));
+        assert_eq!(lines.iter().filter(|x| x.contains("--")).count(), 3);
+    }
+
+    #[test]
+    fn nothing_to_show() {
+        assert_eq!(class_diagram(&objects("int size(void);\n"), |_| None), None);
+    }
+
+    #[test]
+    fn mermaid_names() {
+        assert_eq!(class_id("my-obj.t"), "my_obj_t");
+        assert_eq!(text("say \"hi\""), "say 'hi'");
+    }
+}
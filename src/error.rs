@@ -0,0 +1,27 @@
+use std::fmt::{self, Display};
+
+// failure that stop generation, code is exit code of the process.
+pub(crate) struct Error {
+    pub(crate) code: i32,
+    pub(crate) message: String,
+}
+
+impl Error {
+    pub(crate) fn new(code: i32, message: String) -> Self {
+        Error { code, message }
+    }
+
+    // `<what> is failed.` with reason of failure.
+    pub(crate) fn failed<E>(code: i32, what: &str, e: E) -> Self
+    where
+        E: Display,
+    {
+        Error::new(code, format!("{} is failed.\nError: {}.", what, e))
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
@@ -2,26 +2,17 @@ use std::{
     fs::{read_dir, File, ReadDir},
     io::{self, BufRead},
     path::Path,
-    process::exit,
 };
 
-pub(crate) fn file_list(v: &mut Vec<String>, src: &str) {
-    let files = read_dir(src);
+pub(crate) fn file_list(v: &mut Vec<String>, src: &str, ext: &str) -> io::Result<()> {
+    let files = read_dir(src)?;
 
-    let files = match files {
-        Ok(x) => x,
-        Err(e) => {
-            eprintln!("Error: {}", e);
-            exit(3);
-        }
-    };
-
-    file_extract(v, files, src)
+    file_extract(v, files, ext)
 }
 
-pub(crate) fn file_extract(v: &mut Vec<String>, files: ReadDir, src: &str) {
+pub(crate) fn file_extract(v: &mut Vec<String>, files: ReadDir, ext: &str) -> io::Result<()> {
     for f in files {
-        let f = f.unwrap();
+        let f = f?;
         let ty = f.file_type();
         let ty = match ty {
             Ok(x) => x,
@@ -32,16 +23,17 @@ pub(crate) fn file_extract(v: &mut Vec<String>, files: ReadDir, src: &str) {
         };
 
         if ty.is_dir() {
-            let r = read_dir(f.path().to_str().unwrap()).unwrap();
-            file_extract(v, r, src);
+            let r = read_dir(f.path())?;
+            file_extract(v, r, ext)?;
         } else if ty.is_file() {
             let file = f.path();
             let file = file.to_str().unwrap();
-            if file.ends_with(".h") {
+            if file.ends_with(ext) {
                 v.push(String::from(file));
             }
         }
     }
+    Ok(())
 }
 
 pub(crate) fn read_line<P>(path: P) -> io::Result<io::Lines<io::BufReader<File>>>
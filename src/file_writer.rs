@@ -5,7 +5,14 @@ use std::{
     process::exit,
 };
 
-use crate::{file_reader::read_line, page::Page, parser::str_parser};
+use crate::{
+    config::{Config, Sidebar},
+    error::Error,
+    file_reader::read_line,
+    page::Page,
+    parser::str_parser,
+    utils::c_impl::CImplDocs,
+};
 
 pub(crate) struct GxFile {
     pub(crate) dir: String,
@@ -14,12 +21,23 @@ pub(crate) struct GxFile {
     pub(crate) home_file: String,
 }
 
-pub(crate) fn parse_into_file(fo: &GxFile) -> Result<()> {
+// parsed page with location of its output files, out_side is empty when page
+// has no sidebar of its own.
+pub(crate) struct PageFile {
+    pub(crate) page: Page,
+    pub(crate) out_page: String,
+    pub(crate) out_side: String,
+}
+
+pub(crate) fn parse_into_page(
+    fo: &GxFile,
+    cfg: &Config,
+    impls: &CImplDocs,
+) -> std::result::Result<PageFile, Error> {
     let source_file = &fo.file;
     let source_dir = &fo.dir;
     let out_dir = &fo.out_dir;
     let home = &fo.home_file;
-    let is_home: bool;
 
     let path_separator = if source_file.contains("/") { "/" } else { "\\" };
 
@@ -28,14 +46,15 @@ pub(crate) fn parse_into_file(fo: &GxFile) -> Result<()> {
     let content = match content_file {
         Ok(x) => x,
         Err(e) => {
-            eprintln!("Reading failed.\nError: {}.", e);
-            exit(2);
+            let what = format!("Reading file: {}", source_file);
+            return Err(Error::failed(2, &what, e));
         }
     };
 
-    let content = str_parser(content, home);
-    let page = Page::new();
+    let content = str_parser(content, cfg, impls);
+    let mut page = Page::new();
     page.set_content(Some(content));
+    page.set_toc(cfg.sidebar == Sidebar::Global);
 
     let source_dir = {
         let s_o = if source_dir.contains(path_separator) {
@@ -54,7 +73,7 @@ pub(crate) fn parse_into_file(fo: &GxFile) -> Result<()> {
 
     page.set_path_src(out_file);
 
-    is_home = {
+    let is_home = {
         let home = if home.starts_with(path_separator) {
             &home[1..]
         } else {
@@ -69,19 +88,25 @@ pub(crate) fn parse_into_file(fo: &GxFile) -> Result<()> {
     let file_name = match file_source_name {
         Some(x) => {
             page.set_title(x);
-            create_file_name(x)
+            let name = create_file_name(x);
+            match is_home {
+                true => page.set_url("Home"),
+                false => page.set_url(name.strip_suffix(".md").unwrap_or(&name)),
+            }
+            name
         }
         None => {
-            eprintln!("Error: Cannot change file name.");
-            exit(5);
+            return Err(Error::new(
+                5,
+                String::from("Error: Cannot change file name."),
+            ));
         }
     };
 
     let (page_out_rel, side_out_rel) = match splits {
         Some(x) => create_relative_path(x, &file_name, is_home),
         None => {
-            eprintln!("Error: Construct file output.");
-            exit(5);
+            return Err(Error::new(5, String::from("Error: Construct file output.")));
         }
     };
 
@@ -97,12 +122,22 @@ pub(crate) fn parse_into_file(fo: &GxFile) -> Result<()> {
             (a.clone() + &page_out_rel, a + &side_out_rel)
         }
         None => {
-            eprintln!("Error: invalid output directory.");
-            exit(5);
+            return Err(Error::new(
+                5,
+                String::from("Error: invalid output directory."),
+            ));
         }
     };
-    println!("::> Writing file documentation.");
-    create_file(&page, &out_page, &out_side)
+    // global sidebar is written once for the whole project
+    let out_side = match cfg.sidebar {
+        Sidebar::Page => out_side,
+        Sidebar::Global => String::new(),
+    };
+    Ok(PageFile {
+        page,
+        out_page,
+        out_side,
+    })
 }
 
 pub(crate) fn create_file_name(str: &str) -> String {
@@ -159,15 +194,22 @@ pub(crate) fn extract_source<'a>(
     }
 }
 
-pub(crate) fn create_file(page: &Page, out_page: &str, out_sidebar: &str) -> Result<()> {
-    let path = Path::new(&out_page);
-    let dir = path.parent().unwrap();
-    create_dir_all(dir)?;
-    let mut file = File::create(&path)?;
-    file.write_all(page.render_content().as_bytes())?;
+// sort key of header path listing files of a directory before its sub directories.
+pub(crate) fn tree_key(path: &str) -> (Vec<&str>, &str) {
+    let mut parts = path
+        .split(['/', '\\'])
+        .filter(|x| !x.is_empty())
+        .collect::<Vec<&str>>();
+    let file = parts.pop().unwrap_or_default();
+    (parts, file)
+}
 
-    let path = Path::new(&out_sidebar);
-    let mut file = File::create(&path)?;
-    file.write_all(page.render_side_bar().unwrap().as_bytes())?;
+pub(crate) fn write_file(out: &str, content: &str) -> Result<()> {
+    let path = Path::new(out);
+    if let Some(dir) = path.parent() {
+        create_dir_all(dir)?;
+    }
+    let mut file = File::create(path)?;
+    file.write_all(content.as_bytes())?;
     Ok(())
 }
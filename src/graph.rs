@@ -0,0 +1,348 @@
+use std::collections::HashMap;
+
+// file written next to the pages, for graphviz `dot -Tsvg include-graph.dot`.
+pub(crate) const DOT_FILE: &str = "include-graph.dot";
+
+// quoted include names of header text, system headers are not part of the project.
+pub(crate) fn includes(text: &str) -> Vec<String> {
+    text.lines()
+        .filter_map(|x| x.trim().strip_prefix("#include"))
+        .filter_map(|x| {
+            let x = x.trim().strip_prefix('"')?;
+            x.find('"').map(|end| String::from(&x[..end]))
+        })
+        .collect()
+}
+
+// path of include seen from directory of header, `..` and `.` resolved.
+fn join(dir: &[&str], name: &str) -> String {
+    let mut parts = dir.to_vec();
+    for p in name.split(['/', '\\']) {
+        match p {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            x => parts.push(x),
+        }
+    }
+    format!("/{}", parts.join("/"))
+}
+
+// headers of the project and which ones each includes.
+pub(crate) struct IncludeGraph {
+    files: Vec<String>,
+    edges: Vec<Vec<usize>>,
+}
+
+impl IncludeGraph {
+    // files are source path like `/sub/t.h` with their quoted includes.
+    // include is searched next to the header, then from source root, then by file name
+    // when only one header has it.
+    pub(crate) fn new(files: &[(String, Vec<String>)]) -> Self {
+        let paths = files
+            .iter()
+            .map(|(p, _)| p.replace('\\', "/"))
+            .collect::<Vec<String>>();
+        let index = paths
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (p.as_str(), i))
+            .collect::<HashMap<&str, usize>>();
+        let mut names: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (i, p) in paths.iter().enumerate() {
+            names
+                .entry(p.rsplit('/').next().unwrap())
+                .or_default()
+                .push(i);
+        }
+
+        let edges = files
+            .iter()
+            .zip(&paths)
+            .map(|((_, incs), path)| {
+                let mut dir = path
+                    .split('/')
+                    .filter(|x| !x.is_empty())
+                    .collect::<Vec<_>>();
+                dir.pop();
+                let mut targets = incs
+                    .iter()
+                    .filter_map(|name| {
+                        let by_name = names
+                            .get(name.rsplit(['/', '\\']).next().unwrap())
+                            .filter(|x| x.len() == 1)
+                            .map(|x| x[0]);
+                        index
+                            .get(join(&dir, name).as_str())
+                            .or_else(|| index.get(join(&[], name).as_str()))
+                            .copied()
+                            .or(by_name)
+                    })
+                    .collect::<Vec<usize>>();
+                targets.sort();
+                targets.dedup();
+                targets
+            })
+            .collect();
+        IncludeGraph {
+            files: paths,
+            edges,
+        }
+    }
+
+    fn label(&self, i: usize) -> &str {
+        self.files[i].trim_start_matches('/')
+    }
+
+    // one include cycle of every group of headers including each other, as paths
+    // from first header back to itself.
+    pub(crate) fn cycles(&self) -> Vec<Vec<&str>> {
+        let mut out = vec![];
+        for group in self.components() {
+            let start = group[0];
+            let cyclic = group.len() > 1 || self.edges[start].contains(&start);
+            if !cyclic {
+                continue;
+            }
+            // shortest way back to start inside the group
+            let mut prev: HashMap<usize, usize> = HashMap::new();
+            let mut queue = vec![start];
+            let mut i = 0;
+            let mut end = None;
+            while i < queue.len() && end.is_none() {
+                let n = queue[i];
+                i += 1;
+                for &t in &self.edges[n] {
+                    if t == start {
+                        end = Some(n);
+                        break;
+                    }
+                    if group.contains(&t) && !prev.contains_key(&t) {
+                        prev.insert(t, n);
+                        queue.push(t);
+                    }
+                }
+            }
+            let mut path = vec![start];
+            let mut n = end.unwrap_or(start);
+            while n != start {
+                path.push(n);
+                n = prev[&n];
+            }
+            path[1..].reverse();
+            path.push(start);
+            out.push(path.into_iter().map(|x| self.label(x)).collect());
+        }
+        out
+    }
+
+    // strongly connected headers, tarjan algorithm, in order of first file of each group.
+    fn components(&self) -> Vec<Vec<usize>> {
+        struct State {
+            index: Vec<Option<usize>>,
+            low: Vec<usize>,
+            stack: Vec<usize>,
+            on_stack: Vec<bool>,
+            next: usize,
+            out: Vec<Vec<usize>>,
+        }
+
+        fn visit(g: &IncludeGraph, n: usize, s: &mut State) {
+            s.index[n] = Some(s.next);
+            s.low[n] = s.next;
+            s.next += 1;
+            s.stack.push(n);
+            s.on_stack[n] = true;
+            for &t in &g.edges[n] {
+                match s.index[t] {
+                    None => {
+                        visit(g, t, s);
+                        s.low[n] = s.low[n].min(s.low[t]);
+                    }
+                    Some(x) if s.on_stack[t] => s.low[n] = s.low[n].min(x),
+                    Some(_) => {}
+                }
+            }
+            if Some(s.low[n]) == s.index[n] {
+                let mut group = vec![];
+                while let Some(x) = s.stack.pop() {
+                    s.on_stack[x] = false;
+                    group.push(x);
+                    if x == n {
+                        break;
+                    }
+                }
+                group.sort();
+                s.out.push(group);
+            }
+        }
+
+        let len = self.files.len();
+        let mut state = State {
+            index: vec![None; len],
+            low: vec![0; len],
+            stack: vec![],
+            on_stack: vec![false; len],
+            next: 0,
+            out: vec![],
+        };
+        for n in 0..len {
+            if state.index[n].is_none() {
+                visit(self, n, &mut state);
+            }
+        }
+        state.out.sort();
+        state.out
+    }
+
+    fn mermaid_of(&self, nodes: &[usize], focus: Option<usize>) -> String {
+        let mut lines = vec![String::from("```mermaid"), String::from("graph LR")];
+        for &n in nodes {
+            lines.push(format!("    f{}[\"{}\"]", n, self.label(n)));
+        }
+        for &n in nodes {
+            for t in self.edges[n].iter().filter(|t| nodes.contains(t)) {
+                // neighbourhood only show edges of the focused header
+                if focus.is_none() || focus == Some(n) || focus == Some(*t) {
+                    lines.push(format!("    f{} --> f{}", n, t));
+                }
+            }
+        }
+        if let Some(x) = focus {
+            lines.push(format!("    style f{} stroke-width:3px", x));
+        }
+        lines.push(String::from("```"));
+        lines.join("\n")
+    }
+
+    // whole graph, github render mermaid code block as diagram.
+    pub(crate) fn mermaid(&self) -> Option<String> {
+        if self.edges.iter().all(|x| x.is_empty()) {
+            return None;
+        }
+        let nodes = (0..self.files.len()).collect::<Vec<usize>>();
+        Some(self.mermaid_of(&nodes, None))
+    }
+
+    // header with the headers it includes and the headers including it,
+    // None when it has neither.
+    pub(crate) fn neighbourhood(&self, path: &str) -> Option<String> {
+        let path = path.replace('\\', "/");
+        let focus = self.files.iter().position(|x| *x == path)?;
+        let mut nodes = vec![focus];
+        nodes.extend(&self.edges[focus]);
+        nodes.extend((0..self.files.len()).filter(|n| self.edges[*n].contains(&focus)));
+        nodes.sort();
+        nodes.dedup();
+        match nodes.len() {
+            1 => None,
+            _ => Some(self.mermaid_of(&nodes, Some(focus))),
+        }
+    }
+
+    pub(crate) fn dot(&self) -> String {
+        let mut lines = vec![
+            String::from("digraph includes {"),
+            String::from("    rankdir=LR;"),
+            String::from("    node [shape=box];"),
+        ];
+        for n in 0..self.files.len() {
+            lines.push(format!("    \"{}\";", self.label(n)));
+        }
+        for (n, targets) in self.edges.iter().enumerate() {
+            for t in targets {
+                lines.push(format!(
+                    "    \"{}\" -> \"{}\";",
+                    self.label(n),
+                    self.label(*t)
+                ));
+            }
+        }
+        lines.push(String::from("}"));
+        lines.join("\n") + "\n"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(files: &[(&str, &[&str])]) -> IncludeGraph {
+        let files = files
+            .iter()
+            .map(|(p, incs)| (p.to_string(), incs.iter().map(|x| x.to_string()).collect()))
+            .collect::<Vec<(String, Vec<String>)>>();
+        IncludeGraph::new(&files)
+    }
+
+    #[test]
+    fn quoted_includes_only() {
+        let text = "#include <stdio.h>\n#include \"a.h\"\n  #include  \"sub/b.h\" // b\n#define X \"c.h\"\n";
+        assert_eq!(includes(text), vec!["a.h", "sub/b.h"]);
+    }
+
+    #[test]
+    fn include_resolution() {
+        let g = graph(&[
+            ("/a.h", &["sub/b.h", "missing.h"]),
+            ("/sub/b.h", &["c.h", "../a.h"]),
+            ("/sub/c.h", &[]),
+            ("/other/d.h", &["b.h", "./c.h"]),
+        ]);
+        // next to header, from root, by unique file name
+        assert_eq!(g.edges, vec![vec![1], vec![0, 2], vec![], vec![1, 2]]);
+    }
+
+    #[test]
+    fn ambiguous_name_is_not_resolved() {
+        let g = graph(&[("/x/t.h", &[]), ("/y/t.h", &[]), ("/z/u.h", &["t.h"])]);
+        assert!(g.edges[2].is_empty());
+        assert_eq!(g.mermaid(), None);
+    }
+
+    #[test]
+    fn cycles() {
+        let g = graph(&[
+            ("/a.h", &["b.h"]),
+            ("/b.h", &["c.h"]),
+            ("/c.h", &["a.h"]),
+            ("/d.h", &["d.h"]),
+            ("/e.h", &["a.h"]),
+        ]);
+        assert_eq!(
+            g.cycles(),
+            vec![vec!["a.h", "b.h", "c.h", "a.h"], vec!["d.h", "d.h"]]
+        );
+        assert!(graph(&[("/a.h", &["b.h"]), ("/b.h", &[])])
+            .cycles()
+            .is_empty());
+    }
+
+    #[test]
+    fn neighbourhood() {
+        let g = graph(&[
+            ("/a.h", &["b.h"]),
+            ("/b.h", &["c.h"]),
+            ("/c.h", &[]),
+            ("/d.h", &[]),
+        ]);
+        let md = g.neighbourhood("/b.h").unwrap();
+        assert!(md.starts_with("```mermaid\ngraph LR\n"));
+        assert!(md.contains("    f0 --> f1\n"));
+        assert!(md.contains("    f1 --> f2\n"));
+        assert!(md.contains("    style f1 stroke-width:3px\n"));
+        assert!(!md.contains("d.h"));
+        assert_eq!(g.neighbourhood("/d.h"), None);
+        assert_eq!(g.neighbourhood("/nope.h"), None);
+    }
+
+    #[test]
+    fn dot() {
+        let g = graph(&[("/a.h", &["sub/b.h"]), ("/sub/b.h", &[])]);
+        assert_eq!(
+            g.dot(),
+            "digraph includes {\n    rankdir=LR;\n    node [shape=box];\n    \"a.h\";\n    \"sub/b.h\";\n    \"a.h\" -> \"sub/b.h\";\n}\n"
+        );
+    }
+}
@@ -0,0 +1,96 @@
+// static files of html site, embedded so the binary stay self-contained.
+
+pub(crate) const STYLE: &str = r#"* { box-sizing: border-box; }
+body {
+  margin: 0;
+  display: flex;
+  font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif;
+  color: #1f2328;
+  line-height: 1.5;
+}
+a { color: #0969da; text-decoration: none; }
+a:hover { text-decoration: underline; }
+nav.sidebar {
+  position: sticky;
+  top: 0;
+  height: 100vh;
+  overflow-y: auto;
+  width: 18rem;
+  flex-shrink: 0;
+  padding: 1rem;
+  background: #f6f8fa;
+  border-right: 1px solid #d0d7de;
+  font-size: 0.9rem;
+}
+nav.sidebar ul { list-style: none; margin: 0; padding-left: 0.8rem; }
+nav.sidebar > ul { padding-left: 0; }
+nav.sidebar h3, nav.sidebar h4 { margin: 1rem 0 0.3rem; }
+nav.sidebar li.current > a { font-weight: bold; }
+#search { width: 100%; padding: 0.4rem; border: 1px solid #d0d7de; border-radius: 6px; }
+#search-results li { padding: 0.1rem 0; }
+#search-results .kind { color: #656d76; font-size: 0.8em; margin-left: 0.4rem; }
+main { flex-grow: 1; min-width: 0; max-width: 60rem; padding: 1rem 2rem; }
+main .path { color: #656d76; font-style: italic; margin-top: -0.5rem; }
+section.item { margin: 1.5rem 0; }
+section.item h3 { margin-bottom: 0.3rem; }
+pre {
+  padding: 0.8rem;
+  overflow-x: auto;
+  background: #f6f8fa;
+  border-radius: 6px;
+  font-size: 0.85rem;
+}
+code { font-family: ui-monospace, Consolas, "Liberation Mono", monospace; }
+p code, li code, dd code { background: #eff1f3; padding: 0.1rem 0.3rem; border-radius: 4px; }
+pre .kw { color: #cf222e; }
+pre .ty { color: #8250df; }
+pre .num { color: #0550ae; }
+pre .cm { color: #6e7781; font-style: italic; }
+dl.tags dt { font-weight: bold; margin-top: 0.5rem; }
+dl.tags dd { margin-left: 1.2rem; }
+.note { padding: 0.5rem 1rem; border-left: 4px solid #d4a72c; background: #fff8c5; }
+main table { border-collapse: collapse; }
+main th, main td { border: 1px solid #d0d7de; padding: 0.3rem 0.6rem; text-align: left; }
+"#;
+
+// search box of every page, index is loaded by search-index.js with a script tag
+// because fetch of local file is blocked when page is opened from file://.
+pub(crate) const SEARCH_JS: &str = r#"(function () {
+  var input = document.getElementById("search");
+  var list = document.getElementById("search-results");
+  var index = window.GX_SEARCH_INDEX || [];
+  input.addEventListener("input", function () {
+    var query = input.value.trim().toLowerCase();
+    list.innerHTML = "";
+    if (!query) {
+      return;
+    }
+    index
+      .map(function (e) {
+        return { entry: e, at: e.name.toLowerCase().indexOf(query) };
+      })
+      .filter(function (m) {
+        return m.at >= 0;
+      })
+      .sort(function (a, b) {
+        return a.at - b.at || a.entry.name.length - b.entry.name.length;
+      })
+      .slice(0, 30)
+      .forEach(function (m) {
+        var li = document.createElement("li");
+        var a = document.createElement("a");
+        a.href = m.entry.url;
+        a.textContent = m.entry.name;
+        li.appendChild(a);
+        var kind = document.createElement("span");
+        kind.className = "kind";
+        kind.textContent = m.entry.kind;
+        li.appendChild(kind);
+        if (m.entry.summary) {
+          li.title = m.entry.summary;
+        }
+        list.appendChild(li);
+      });
+  });
+})();
+"#;
@@ -0,0 +1,172 @@
+use crate::symbols::{escape_html, tokens, Linker};
+
+const KEYWORDS: [&str; 34] = [
+    "auto",
+    "break",
+    "case",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "else",
+    "enum",
+    "extern",
+    "for",
+    "goto",
+    "if",
+    "inline",
+    "register",
+    "restrict",
+    "return",
+    "sizeof",
+    "static",
+    "struct",
+    "switch",
+    "typedef",
+    "union",
+    "volatile",
+    "while",
+    "signed",
+    "unsigned",
+    "short",
+    "long",
+    "_Alignas",
+    "_Atomic",
+    "_Noreturn",
+    "_Static_assert",
+    "_Thread_local",
+];
+
+const TYPES: [&str; 22] = [
+    "void",
+    "char",
+    "int",
+    "float",
+    "double",
+    "bool",
+    "_Bool",
+    "size_t",
+    "ssize_t",
+    "ptrdiff_t",
+    "intptr_t",
+    "uintptr_t",
+    "int8_t",
+    "int16_t",
+    "int32_t",
+    "int64_t",
+    "uint8_t",
+    "uint16_t",
+    "uint32_t",
+    "uint64_t",
+    "FILE",
+    "va_list",
+];
+
+// C declaration as html with keyword, builtin type and number highlighted,
+// documented type is linked to its page.
+pub(crate) fn highlight(code: &str, linker: &Linker, own: &str) -> String {
+    let mut out = String::new();
+    let mut comment = false;
+    for (is_ident, token) in tokens(code) {
+        if !is_ident {
+            // comment run until end of line
+            for (i, line) in token.split('\n').enumerate() {
+                if i > 0 {
+                    if comment {
+                        out.push_str("</span>");
+                        comment = false;
+                    }
+                    out.push('\n');
+                }
+                match line.find("//").or_else(|| line.find("/*")) {
+                    Some(x) if !comment => {
+                        out.push_str(&escape_html(&line[..x]));
+                        out.push_str("<span class=\"cm\">");
+                        out.push_str(&escape_html(&line[x..]));
+                        comment = true;
+                    }
+                    _ => out.push_str(&escape_html(line)),
+                }
+            }
+            continue;
+        }
+
+        if comment {
+            out.push_str(&escape_html(token));
+            continue;
+        }
+        let class = if KEYWORDS.contains(&token) {
+            Some("kw")
+        } else if TYPES.contains(&token) {
+            Some("ty")
+        } else if token.starts_with(|c: char| c.is_ascii_digit()) {
+            Some("num")
+        } else {
+            None
+        };
+        match (class, linker.type_url(token, own)) {
+            (Some(c), _) => out.push_str(&format!("<span class=\"{}\">{}</span>", c, token)),
+            (None, Some(x)) => {
+                out.push_str(&format!("<a class=\"ty\" href=\"{}\">{}</a>", x, token))
+            }
+            (None, None) => out.push_str(token),
+        }
+    }
+    if comment {
+        out.push_str("</span>");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{ir::DocKind, symbols::Symbol, symbols::SymbolTable};
+
+    fn linker() -> Linker {
+        let obj = Symbol {
+            name: String::from("struct Obj"),
+            kind: DocKind::Object,
+            page: String::from("T.html"),
+            anchor: String::from("struct-obj"),
+            file: String::from("/t.h"),
+            summary: String::new(),
+            uses: vec![],
+            deprecated: None,
+        };
+        let names = vec![String::from("struct Obj"), String::from("Obj")];
+        let table = SymbolTable::from_symbols(&[vec![(names, obj)]]);
+        Linker::new(Arc::new(table), "Home.html", "/home.h")
+    }
+
+    #[test]
+    fn tokens_classes() {
+        assert_eq!(
+            highlight("static const int n = 16;", &linker(), ""),
+            "<span class=\"kw\">static</span> <span class=\"kw\">const</span> <span class=\"ty\">int</span> n = <span class=\"num\">16</span>;"
+        );
+    }
+
+    #[test]
+    fn linked_symbol() {
+        assert_eq!(
+            highlight("Obj *gx_new(struct Obj *a);", &linker(), ""),
+            "<a class=\"ty\" href=\"T.html#struct-obj\">Obj</a> *gx_new(<span class=\"kw\">struct</span> <a class=\"ty\" href=\"T.html#struct-obj\">Obj</a> *a);"
+        );
+        // item does not link to itself
+        assert_eq!(
+            highlight("struct Obj {", &linker(), "struct Obj"),
+            "<span class=\"kw\">struct</span> Obj {"
+        );
+    }
+
+    #[test]
+    fn comment_and_escape() {
+        assert_eq!(
+            highlight("int a; // a < Obj\nint b;", &linker(), ""),
+            "<span class=\"ty\">int</span> a; <span class=\"cm\">// a &lt; Obj</span>\n<span class=\"ty\">int</span> b;"
+        );
+    }
+}
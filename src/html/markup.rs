@@ -0,0 +1,220 @@
+use crate::symbols::escape_html;
+
+// markdown of description to html, only what doc comment use:
+// code span, link, bold and italic.
+pub(crate) fn inline(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if c == '`' {
+            if let Some(end) = rest[1..].find('`') {
+                out.push_str(&format!("<code>{}</code>", escape_html(&rest[1..end + 1])));
+                rest = &rest[end + 2..];
+                continue;
+            }
+        }
+        if c == '[' {
+            if let Some((label, url, len)) = link(rest) {
+                out.push_str(&format!(
+                    "<a href=\"{}\">{}</a>",
+                    escape_html(url),
+                    inline(label)
+                ));
+                rest = &rest[len..];
+                continue;
+            }
+        }
+        if rest.starts_with("**") {
+            if let Some(end) = rest[2..].find("**").filter(|x| *x > 0) {
+                out.push_str(&format!("<strong>{}</strong>", inline(&rest[2..end + 2])));
+                rest = &rest[end + 4..];
+                continue;
+            }
+        }
+        if c == '*' && !rest[1..].starts_with([' ', '*']) {
+            if let Some(end) = em_end(&rest[1..]).filter(|x| *x > 0) {
+                out.push_str(&format!("<em>{}</em>", inline(&rest[1..end + 1])));
+                rest = &rest[end + 2..];
+                continue;
+            }
+        }
+        out.push_str(&escape_html(&rest[..c.len_utf8()]));
+        rest = &rest[c.len_utf8()..];
+    }
+    out
+}
+
+// closing `*` of italic, `**` of bold and `*` of code span inside are skipped.
+fn em_end(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'`' => match text[i + 1..].find('`') {
+                Some(x) => i += x + 2,
+                None => i += 1,
+            },
+            b'*' if bytes.get(i + 1) == Some(&b'*') => i += 2,
+            b'*' => return Some(i),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+// `[label](url)` at start of text, with length of whole link.
+fn link(text: &str) -> Option<(&str, &str, usize)> {
+    let close = text.find("](")?;
+    let label = &text[1..close];
+    if label.contains('[') {
+        return None;
+    }
+    let end = text[close + 2..].find(')')? + close + 2;
+    Some((label, &text[close + 2..end], end + 1))
+}
+
+// markdown of main comment to html: heading, list, fenced code and paragraph.
+pub(crate) fn blocks(text: &str) -> String {
+    let mut out: Vec<String> = vec![];
+    let mut para: Vec<&str> = vec![];
+    let mut list: Vec<&str> = vec![];
+    let mut code: Option<Vec<&str>> = None;
+
+    fn flush(out: &mut Vec<String>, para: &mut Vec<&str>, list: &mut Vec<&str>) {
+        if !para.is_empty() {
+            out.push(format!("<p>{}</p>", inline(&para.join(" "))));
+            para.clear();
+        }
+        if !list.is_empty() {
+            let items = list
+                .iter()
+                .map(|x| format!("<li>{}</li>", inline(x)))
+                .collect::<Vec<String>>();
+            out.push(format!("<ul>\n{}\n</ul>", items.join("\n")));
+            list.clear();
+        }
+    }
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if let Some(lines) = code.as_mut() {
+            match trimmed.starts_with("```") {
+                true => {
+                    out.push(format!(
+                        "<pre><code>{}</code></pre>",
+                        escape_html(&lines.join("\n"))
+                    ));
+                    code = None;
+                }
+                false => lines.push(line),
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") {
+            flush(&mut out, &mut para, &mut list);
+            code = Some(vec![]);
+        } else if trimmed.is_empty() {
+            flush(&mut out, &mut para, &mut list);
+        } else if trimmed.starts_with('#') {
+            flush(&mut out, &mut para, &mut list);
+            let level = trimmed.chars().take_while(|c| *c == '#').count();
+            let level = (level + 1).min(6);
+            out.push(format!(
+                "<h{}>{}</h{}>",
+                level,
+                inline(trimmed.trim_start_matches('#').trim()),
+                level
+            ));
+        } else if let Some(x) = trimmed
+            .strip_prefix("* ")
+            .or_else(|| trimmed.strip_prefix("- "))
+        {
+            if !para.is_empty() {
+                flush(&mut out, &mut para, &mut list);
+            }
+            list.push(x);
+        } else {
+            if !list.is_empty() {
+                flush(&mut out, &mut para, &mut list);
+            }
+            para.push(trimmed);
+        }
+    }
+    if let Some(lines) = code {
+        out.push(format!(
+            "<pre><code>{}</code></pre>",
+            escape_html(&lines.join("\n"))
+        ));
+    }
+    flush(&mut out, &mut para, &mut list);
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_text() {
+        assert_eq!(
+            inline("a < b && \"c\""),
+            "a &lt; b &amp;&amp; &quot;c&quot;"
+        );
+        assert_eq!(inline("`<T>`"), "<code>&lt;T&gt;</code>");
+        assert_eq!(
+            inline("[a & b](x.html?a=1&b=\"2\")"),
+            "<a href=\"x.html?a=1&amp;b=&quot;2&quot;\">a &amp; b</a>"
+        );
+    }
+
+    #[test]
+    fn nested_emphasis() {
+        assert_eq!(
+            inline("**bold *it* `c*d`** rest"),
+            "<strong>bold <em>it</em> <code>c*d</code></strong> rest"
+        );
+        assert_eq!(inline("*it **b** *"), "<em>it <strong>b</strong> </em>");
+        assert_eq!(inline("*a `b*c` d*"), "<em>a <code>b*c</code> d</em>");
+        assert_eq!(
+            inline("[**b** `c`](u)"),
+            "<a href=\"u\"><strong>b</strong> <code>c</code></a>"
+        );
+        // lone marks are kept
+        assert_eq!(inline("a * b ** c"), "a * b ** c");
+        assert_eq!(inline("`open"), "`open");
+    }
+
+    #[test]
+    fn block_kinds() {
+        let html = blocks(concat!(
+            "## Usage\n",
+            "first line\n",
+            "second *line*\n",
+            "\n",
+            "* one\n",
+            "- two\n",
+            "```c\n",
+            "if (a < b) { *p = 1; }\n",
+            "```\n",
+            "end",
+        ));
+        assert_eq!(
+            html,
+            concat!(
+                "<h3>Usage</h3>\n",
+                "<p>first line second <em>line</em></p>\n",
+                "<ul>\n<li>one</li>\n<li>two</li>\n</ul>\n",
+                "<pre><code>if (a &lt; b) { *p = 1; }</code></pre>\n",
+                "<p>end</p>",
+            )
+        );
+    }
+
+    #[test]
+    fn unclosed_fence() {
+        assert_eq!(
+            blocks("text\n```\n**x** <y>"),
+            "<p>text</p>\n<pre><code>**x** &lt;y&gt;</code></pre>"
+        );
+    }
+}
@@ -0,0 +1,716 @@
+use std::{collections::HashSet, path::Path, sync::Arc};
+
+use assets::{SEARCH_JS, STYLE};
+use highlight::highlight;
+use markup::{blocks, inline};
+
+use crate::{
+    config::{Config, Fields},
+    deprecated::entries,
+    error::Error,
+    file_writer::{write_file, PageFile},
+    index::{letter_anchor, letters, symbol_file},
+    ir::{DocDetail, DocFile, DocItem, DocKind, DocProject},
+    json::Json,
+    page::{
+        view::{FieldRow, ItemRows, TagRow},
+        Page,
+    },
+    symbols::{escape_html, FileSymbols, Linker, Symbol, SymbolTable},
+    utils::doc_tag::tag_title,
+    worker::parallel_map,
+};
+
+mod assets;
+mod highlight;
+mod markup;
+
+// page listing every symbol, index.html is already taken by home page.
+const INDEX_HTML: &str = "symbol-index.html";
+const DEPRECATED_HTML: &str = "deprecated.html";
+
+// file name of html page of wiki page name.
+fn html_name(url: &str) -> String {
+    match url {
+        "Home" => String::from("index.html"),
+        x => format!("{}.html", x),
+    }
+}
+
+// write static site: one page per header, search index and assets.
+pub(crate) fn write_html(cfg: &Config, pages: Vec<PageFile>) -> Result<(), Error> {
+    let mut project =
+        DocProject::from_pages(&pages.iter().map(|x| &x.page).collect::<Vec<&Page>>());
+    project
+        .files
+        .iter_mut()
+        .for_each(|f| f.url = html_name(&f.url));
+
+    println!(":> Building symbol table.");
+    let symbols = project
+        .files
+        .iter()
+        .map(|f| SymbolTable::file_symbols(f, cfg.include_private))
+        .collect::<Vec<_>>();
+    let table = Arc::new(SymbolTable::from_symbols(&symbols));
+
+    let items = project.files.iter().zip(&pages).collect::<Vec<_>>();
+    parallel_map(&items, cfg.jobs, |(file, pf)| {
+        println!(":> Writing documentation of file: {}.", &file.path);
+        let linker = Linker::new(table.clone(), &file.url, &file.path);
+        let html = render_page(cfg, &project, file, &pf.page, &linker);
+        let out = Path::new(&cfg.out).join(&file.url);
+        match write_file(&out.to_string_lossy(), &html) {
+            Ok(_) => {
+                println!("::> Documentation for file {} : Success.", &file.path);
+                Ok(())
+            }
+            Err(e) => {
+                let what = format!("Documentation file: {}", &file.path);
+                Err(Error::failed(7, &what, e))
+            }
+        }
+    })
+    .into_iter()
+    .collect::<Result<(), Error>>()?;
+
+    let files = symbols.iter().collect::<Vec<_>>();
+    let out = Path::new(&cfg.out).join(INDEX_HTML);
+    println!(":> Writing symbol index {}.", out.display());
+    let html = layout(
+        "Index",
+        &files_html(&project, INDEX_HTML),
+        None,
+        &index_html(&files),
+    );
+    write_file(&out.to_string_lossy(), &html)
+        .map_err(|e| Error::failed(7, "Writing symbol index", e))?;
+
+    let out = Path::new(&cfg.out).join(DEPRECATED_HTML);
+    println!(":> Writing deprecated page {}.", out.display());
+    let html = layout(
+        "Deprecated",
+        &files_html(&project, DEPRECATED_HTML),
+        None,
+        &deprecated_html(&files),
+    );
+    write_file(&out.to_string_lossy(), &html)
+        .map_err(|e| Error::failed(7, "Writing deprecated page", e))?;
+
+    let index = search_index(cfg, &project).render();
+    let assets = [
+        ("style.css", String::from(STYLE)),
+        ("search.json", index.clone()),
+        (
+            "search-index.js",
+            format!("window.GX_SEARCH_INDEX = {};\n", index),
+        ),
+    ];
+    for (name, content) in assets {
+        let out = Path::new(&cfg.out).join(name);
+        write_file(&out.to_string_lossy(), &content)
+            .map_err(|e| Error::failed(7, &format!("Writing {}", name), e))?;
+    }
+    Ok(())
+}
+
+// every visible symbol and page, loaded by search box of each page.
+fn search_index(cfg: &Config, project: &DocProject) -> Json {
+    let mut entries = vec![];
+    for file in &project.files {
+        entries.push(Json::obj(vec![
+            ("name", Json::str(&file.title)),
+            ("kind", Json::str("file")),
+            ("url", Json::str(&file.url)),
+            ("path", Json::str(&file.path)),
+            ("summary", Json::Null),
+        ]));
+        for item in &file.items {
+            if !visible(cfg, item) || item.kind == DocKind::Include {
+                continue;
+            }
+            let anchor = match item.anchor.as_ref() {
+                Some(x) => x,
+                None => continue,
+            };
+            entries.push(Json::obj(vec![
+                ("name", Json::str(&item.title)),
+                ("kind", Json::str(item.kind.into_str())),
+                ("url", Json::str(&format!("{}#{}", file.url, anchor))),
+                ("path", Json::str(&file.path)),
+                (
+                    "summary",
+                    match item.summary.as_ref() {
+                        Some(x) => Json::str(x),
+                        None => Json::Null,
+                    },
+                ),
+            ]));
+        }
+    }
+    Json::Arr(entries)
+}
+
+// deprecated symbols as table, the same as markdown deprecated page.
+fn deprecated_html(files: &[&FileSymbols]) -> String {
+    let link = |s: &Symbol, name: &str| {
+        format!(
+            "<a href=\"{}#{}\"><code>{}</code></a>",
+            s.page,
+            s.anchor,
+            escape_html(name)
+        )
+    };
+    let entries = entries(files);
+    if entries.is_empty() {
+        return String::from("<h1>Deprecated</h1>\n<p><em>No deprecated symbols.</em></p>");
+    }
+    let rows = entries
+        .iter()
+        .map(|(s, replacement)| {
+            let replacement = match replacement {
+                Some((x, Some(r))) => link(r, x),
+                Some((x, None)) => format!("<code>{}</code>", escape_html(x)),
+                None => String::new(),
+            };
+            format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                link(s, &s.name),
+                escape_html(symbol_file(s)),
+                inline(s.deprecated.as_deref().unwrap_or_default()),
+                replacement
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    format!(
+        "<h1>Deprecated</h1>\n<table>\n<tr><th>Symbol</th><th>Header</th><th>Reason</th><th>Replacement</th></tr>\n{}\n</table>",
+        rows
+    )
+}
+
+// symbols grouped by letter and kind, the same as markdown index page.
+fn index_html(files: &[&FileSymbols]) -> String {
+    let letters = letters(files);
+    let nav = letters
+        .iter()
+        .map(|(l, _)| format!("<a href=\"#{}\">{}</a>", letter_anchor(l), escape_html(l)))
+        .collect::<Vec<String>>()
+        .join(" · ");
+    let mut out = vec![format!("<h1>Index</h1>\n<p>{}</p>\n<hr>", nav)];
+    for (l, kinds) in letters {
+        out.push(format!(
+            "<h2 id=\"{}\">{}</h2>",
+            letter_anchor(&l),
+            escape_html(&l)
+        ));
+        for (kind, symbols) in kinds {
+            let items = symbols
+                .iter()
+                .map(|s| {
+                    let summary = match s.summary.is_empty() {
+                        true => String::new(),
+                        false => format!(" — {}", inline(&s.summary)),
+                    };
+                    format!(
+                        "<li><a href=\"{}#{}\"><code>{}</code></a>{} <span class=\"path\">{}</span></li>",
+                        s.page,
+                        s.anchor,
+                        escape_html(&s.name),
+                        summary,
+                        escape_html(symbol_file(s))
+                    )
+                })
+                .collect::<Vec<String>>()
+                .concat();
+            out.push(format!("<h4>{}</h4>\n<ul>{}</ul>", kind, items));
+        }
+    }
+    out.join("\n")
+}
+
+fn visible(cfg: &Config, item: &DocItem) -> bool {
+    !item.private || cfg.include_private
+}
+
+fn render_page(
+    cfg: &Config,
+    project: &DocProject,
+    file: &DocFile,
+    page: &Page,
+    linker: &Linker,
+) -> String {
+    let urls = project
+        .files
+        .iter()
+        .map(|f| f.url.as_str())
+        .collect::<HashSet<&str>>();
+    let page_url = |url: &str| Some(html_name(url)).filter(|x| urls.contains(x.as_str()));
+
+    let side = match page.get_content() {
+        Some(x) => x.create_side_panel().render_html(page_url),
+        None => String::new(),
+    };
+
+    let main = match file.main.as_ref() {
+        Some(x) => blocks(x),
+        None => String::new(),
+    };
+
+    let items_in = |group: Option<&str>| {
+        file.items
+            .iter()
+            .filter(|i| !i.private && i.group.as_deref() == group)
+            .collect::<Vec<&DocItem>>()
+    };
+    let mut sections = vec![];
+    for (title, kind) in [
+        ("Objects", DocKind::Object),
+        ("Functions", DocKind::Function),
+        ("Includes", DocKind::Include),
+    ] {
+        let items = items_in(None)
+            .into_iter()
+            .filter(|i| i.kind == kind)
+            .collect::<Vec<&DocItem>>();
+        sections.push(section(title, None, &items, cfg.fields, linker, &page_url));
+    }
+    for g in &file.groups {
+        sections.push(section(
+            &g.name,
+            g.intro.as_deref(),
+            &items_in(Some(&g.name)),
+            cfg.fields,
+            linker,
+            &page_url,
+        ));
+    }
+    if cfg.include_private {
+        let items = file.items.iter().filter(|i| i.private).collect::<Vec<_>>();
+        sections.push(section(
+            "Internal",
+            Some("<p class=\"note\"><strong>Internal:</strong> not part of public API, may change without notice.</p>"),
+            &items,
+            cfg.fields,
+            linker,
+            &page_url,
+        ));
+    }
+
+    let body = format!(
+        "<h1>{}</h1>\n<p class=\"path\">{}</p>\n<hr>\n{}\n{}",
+        escape_html(&file.title),
+        escape_html(&file.path),
+        main,
+        sections.concat()
+    );
+    layout(
+        &file.title,
+        &files_html(project, &file.url),
+        Some(&side),
+        &body,
+    )
+}
+
+// list of every page, current one is highlighted.
+fn files_html(project: &DocProject, current: &str) -> String {
+    project
+        .files
+        .iter()
+        .map(|f| {
+            let class = match f.url == current {
+                true => " class=\"current\"",
+                false => "",
+            };
+            format!(
+                "<li{}><a href=\"{}\" title=\"{}\">{}</a></li>",
+                class,
+                f.url,
+                escape_html(&f.path),
+                escape_html(&f.title)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// html page with search box, file list and page sidebar around body.
+fn layout(title: &str, files: &str, side: Option<&str>, body: &str) -> String {
+    let side = match side {
+        Some(x) => format!("<h3>{}</h3>\n{}\n", escape_html(title), x),
+        None => String::new(),
+    };
+    format!(
+        "<!DOCTYPE html>
+<html lang=\"en\">
+<head>
+<meta charset=\"utf-8\">
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">
+<title>{title}</title>
+<link rel=\"stylesheet\" href=\"style.css\">
+<script src=\"search-index.js\"></script>
+</head>
+<body>
+<nav class=\"sidebar\">
+<input id=\"search\" type=\"search\" placeholder=\"Search\" autocomplete=\"off\">
+<ul id=\"search-results\"></ul>
+<p><a href=\"{index}\">Symbol index</a> · <a href=\"{deprecated}\">Deprecated</a></p>
+<h3>Files</h3>
+<ul>
+{files}
+</ul>
+{side}</nav>
+<main>
+{body}
+</main>
+<script>
+{script}</script>
+</body>
+</html>
+",
+        title = escape_html(title),
+        index = INDEX_HTML,
+        deprecated = DEPRECATED_HTML,
+        files = files,
+        side = side,
+        body = body,
+        script = SEARCH_JS
+    )
+}
+
+// intro of group is markdown, intro of internal section is already html.
+fn section<F>(
+    title: &str,
+    intro: Option<&str>,
+    items: &[&DocItem],
+    fields: Fields,
+    linker: &Linker,
+    page_url: &F,
+) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    if items.is_empty() {
+        return String::new();
+    }
+    let intro = match intro {
+        Some(x) if x.starts_with('<') => format!("{}\n", x),
+        Some(x) => format!("<p>{}</p>\n", inline(x)),
+        None => String::new(),
+    };
+    let items = items
+        .iter()
+        .map(|i| item_html(i, fields, linker, page_url))
+        .collect::<Vec<String>>()
+        .join("\n");
+    format!(
+        "<h2>{}</h2>\n{}<hr>\n{}\n",
+        escape_html(title),
+        intro,
+        items
+    )
+}
+
+fn item_html<F>(item: &DocItem, fields: Fields, linker: &Linker, page_url: &F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let code = match &item.detail {
+        DocDetail::Include { target, url } => {
+            // system header keep its angle bracket
+            let system = target.starts_with('<');
+            let target = escape_html(target);
+            let target = match page_url(url) {
+                Some(x) => format!("<a href=\"{}\">{}</a>", x, target),
+                None => target,
+            };
+            match system {
+                true => format!("<span class=\"kw\">#include</span> {}", target),
+                false => format!("<span class=\"kw\">#include</span> \"{}\"", target),
+            }
+        }
+        _ => highlight(&item.code, linker, &item.title),
+    };
+    let (id, heading) = match item.anchor.as_ref() {
+        Some(x) => (
+            format!(" id=\"{}\"", x),
+            format!("<a href=\"#{}\">{}</a>", x, escape_html(&item.title)),
+        ),
+        None => (String::new(), escape_html(&item.title)),
+    };
+    let line = |x: &str| item.comment.line_of(x, item.line);
+    let text = |x: &str| inline(&linker.link_text(&linker.link_intra(x, &line), &item.title));
+    let summary = match item.summary.as_ref() {
+        Some(x) => format!("\n<p>{}</p>", text(x)),
+        None => String::new(),
+    };
+    let deprecated = match item.deprecated.as_deref() {
+        Some("") => String::from("\n<p class=\"note\"><strong>Deprecated</strong></p>"),
+        Some(x) => format!(
+            "\n<p class=\"note\"><strong>Deprecated:</strong> {}</p>",
+            text(x)
+        ),
+        None => String::new(),
+    };
+    let used_by = item
+        .anchor
+        .as_ref()
+        .map(|x| linker.used_by(x))
+        .unwrap_or_default()
+        .iter()
+        .map(|(name, url, file)| {
+            let file = match file.is_empty() {
+                true => String::new(),
+                false => format!(" <span class=\"path\">{}</span>", escape_html(file)),
+            };
+            format!(
+                "<a href=\"{}\"><code>{}</code></a>{}",
+                url,
+                escape_html(name),
+                file
+            )
+        })
+        .collect::<Vec<String>>();
+    let used_by = match used_by.is_empty() {
+        true => String::new(),
+        false => format!("\n<p><strong>Used by</strong> {}</p>", used_by.join(", ")),
+    };
+    let show_code = fields != Fields::Table;
+    let fields = match fields {
+        Fields::Code => String::new(),
+        Fields::Table | Fields::Both => fields_html(&item.rows.fields, linker, item, &text),
+    };
+    // declaration is still shown when table has nothing to show
+    let code = match show_code || fields.is_empty() {
+        true => format!("\n<pre><code>{}</code></pre>", code),
+        false => String::new(),
+    };
+    format!(
+        "<section class=\"item\"{}>\n<h3>{}</h3>{}{}{}{}{}{}{}\n</section>",
+        id,
+        heading,
+        code,
+        deprecated,
+        summary,
+        fields,
+        signature_html(&item.rows, linker, item, &text),
+        tags_html(&item.rows.tags, linker, &text),
+        used_by
+    )
+}
+
+fn fields_html<F>(rows: &[FieldRow], linker: &Linker, item: &DocItem, text: &F) -> String
+where
+    F: Fn(&str) -> String,
+{
+    if rows.is_empty() {
+        return String::new();
+    }
+    let rows = rows
+        .iter()
+        .map(|f| {
+            format!(
+                "<tr><td><code>{}</code></td><td><code>{}</code></td><td>{}</td><td>{}</td></tr>",
+                escape_html(&f.name),
+                highlight(&f.ty, linker, &item.title),
+                match f.extent.is_empty() {
+                    true => String::new(),
+                    false => format!("<code>{}</code>", escape_html(&f.extent)),
+                },
+                text(&f.desc)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    format!(
+        "\n<table class=\"fields\">\n<tr><th>Field</th><th>Type</th><th>Array/Bits</th><th>Description</th></tr>\n{}\n</table>",
+        rows
+    )
+}
+
+// parameters table and return of function.
+fn signature_html<F>(rows: &ItemRows, linker: &Linker, item: &DocItem, text: &F) -> String
+where
+    F: Fn(&str) -> String,
+{
+    let mut out = String::new();
+    if !rows.params.is_empty() {
+        let params = rows
+            .params
+            .iter()
+            .map(|p| {
+                let name = match p.name.is_empty() {
+                    true => String::new(),
+                    false => format!("<code>{}</code>", escape_html(&p.name)),
+                };
+                format!(
+                    "<tr><td>{}</td><td><code>{}</code></td><td>{}</td><td>{}</td></tr>",
+                    name,
+                    highlight(&p.ty, linker, &item.title),
+                    p.direction,
+                    text(&p.desc)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+        out.push_str(&format!(
+            "\n<table class=\"params\">\n<tr><th>Parameter</th><th>Type</th><th>Direction</th><th>Description</th></tr>\n{}\n</table>",
+            params
+        ));
+    }
+    if let Some(r) = rows.returns.as_ref() {
+        let desc = match r.desc.is_empty() {
+            true => String::new(),
+            false => format!(": {}", text(&r.desc)),
+        };
+        out.push_str(&format!(
+            "\n<p><strong>Returns</strong> <code>{}</code>{}</p>",
+            highlight(&r.ty, linker, &item.title),
+            desc
+        ));
+    }
+    out
+}
+
+// tags kept below description as definition list, tags of the same kind
+// share one title.
+fn tags_html<F>(rows: &[TagRow], linker: &Linker, text: &F) -> String
+where
+    F: Fn(&str) -> String,
+{
+    if rows.is_empty() {
+        return String::new();
+    }
+    let mut out: Vec<String> = vec![];
+    for row in rows {
+        out.push(format!("<dt>{}</dt>", tag_title(&row.tag)));
+        for t in &row.values {
+            let dd = match t.get_tag() {
+                "see" => {
+                    let arg = t.get_arg().unwrap_or_default();
+                    let name = format!("<code>{}</code>", escape_html(arg));
+                    let name = match linker.symbol_url(arg) {
+                        Some(x) => format!("<a href=\"{}\">{}</a>", x, name),
+                        None => name,
+                    };
+                    format!("{} {}", name, text(t.get_rest()))
+                }
+                _ => text(t.get_text()),
+            };
+            out.push(format!("<dd>{}</dd>", dd.trim_end()));
+        }
+    }
+    format!("\n<dl class=\"tags\">\n{}\n</dl>", out.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufRead;
+
+    use super::*;
+    use crate::{parser::str_parser, utils::c_impl::CImplDocs};
+
+    fn project(text: &str) -> DocProject {
+        let args = ["gx_md", "-src", "src", "-o", "out", "-h", "t.h"];
+        let cfg = Config::from_args(args.iter().map(|x| x.to_string()).collect()).unwrap();
+        let mut page = Page::new();
+        page.set_content(Some(str_parser(
+            text.as_bytes().lines(),
+            &cfg,
+            &CImplDocs::new(),
+        )));
+        page.set_path_src("/t.h");
+        page.set_url("T.html");
+        page.set_title("t.h");
+        DocProject::from_pages(&[&page])
+    }
+
+    fn items(text: &str, fields: Fields) -> Vec<String> {
+        let project = project(text);
+        let file = &project.files[0];
+        let table = SymbolTable::from_symbols(&[SymbolTable::file_symbols(file, false)]);
+        let linker = Linker::new(Arc::new(table), &file.url, &file.path);
+        file.items
+            .iter()
+            .map(|i| item_html(i, fields, &linker, &|_: &str| None))
+            .collect()
+    }
+
+    #[test]
+    fn function_tables() {
+        let html = items(
+            concat!(
+                "//! open it\n",
+                "//! @param[out] buf the <buffer>\n",
+                "//! @param n size\n",
+                "//! @return code\n",
+                "//! @note slow\n",
+                "//! @note blocking\n",
+                "//! @see gx_open\n",
+                "int gx_open(char *buf, int n);\n",
+            ),
+            Fields::Both,
+        )
+        .concat();
+        assert!(html.contains("<p>open it</p>"), "{}", html);
+        assert!(
+            html.contains("<tr><td><code>buf</code></td><td><code><span class=\"ty\">char</span>*</code></td><td>out</td><td>the &lt;buffer&gt;</td></tr>"),
+            "{}",
+            html
+        );
+        assert!(html.contains("<td>in</td><td>size</td>"), "{}", html);
+        assert!(
+            html.contains(
+                "<p><strong>Returns</strong> <code><span class=\"ty\">int</span></code>: code</p>"
+            ),
+            "{}",
+            html
+        );
+        assert!(
+            html.contains("<dt>Note</dt>\n<dd>slow</dd>\n<dd>blocking</dd>\n<dt>See also</dt>\n<dd><a href=\"#gx_open\"><code>gx_open</code></a></dd>"),
+            "{}",
+            html
+        );
+        assert!(!html.contains("Parameters"), "{}", html);
+        assert!(!html.contains('@'), "{}", html);
+    }
+
+    #[test]
+    fn struct_fields_table() {
+        let text = concat!(
+            "//! a point\n",
+            "struct Pt {\n",
+            "    int x; //!< x value\n",
+            "    char name[8];\n",
+            "    unsigned flags : 3;\n",
+            "};\n",
+            "\n",
+            "//! a line\n",
+            "struct Line {\n",
+            "    struct Pt a; //!< start, see [`Pt`]\n",
+            "};\n",
+        );
+        let html = items(text, Fields::Both);
+        assert!(html[0].contains("<pre><code>"), "{}", html[0]);
+        assert!(
+            html[0].contains("<tr><td><code>x</code></td><td><code><span class=\"ty\">int</span></code></td><td></td><td>x value</td></tr>"),
+            "{}",
+            html[0]
+        );
+        assert!(html[0].contains("<td><code>[8]</code></td>"), "{}", html[0]);
+        assert!(html[0].contains("<td><code>: 3</code></td>"), "{}", html[0]);
+        assert!(
+            html[1].contains("<td><code><span class=\"kw\">struct</span> <a class=\"ty\" href=\"#struct-pt\">Pt</a></code></td><td></td><td>start, see <a href=\"#struct-pt\"><code>Pt</code></a></td>"),
+            "{}",
+            html[1]
+        );
+
+        let html = items(text, Fields::Table);
+        assert!(!html[0].contains("<pre>"), "{}", html[0]);
+        assert!(html[0].contains("<table class=\"fields\">"), "{}", html[0]);
+
+        let html = items(text, Fields::Code);
+        assert!(html[0].contains("<pre><code>"), "{}", html[0]);
+        assert!(!html[0].contains("<table"), "{}", html[0]);
+    }
+}
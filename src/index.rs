@@ -0,0 +1,238 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    ir::DocKind,
+    slug::slug,
+    symbols::{FileSymbols, Symbol},
+};
+
+// page listing every documented symbol of the project.
+pub(crate) const INDEX_PAGE: &str = "Index";
+
+// kinds in the order they are listed under a letter.
+const KINDS: [&str; 4] = ["Functions", "Structs", "Unions", "Types"];
+
+// object kind is read from its title, typedef alias has no keyword.
+fn kind_title(symbol: &Symbol) -> &'static str {
+    match symbol.kind {
+        DocKind::Function => KINDS[0],
+        _ if symbol.name.starts_with("struct ") => KINDS[1],
+        _ if symbol.name.starts_with("union ") => KINDS[2],
+        _ => KINDS[3],
+    }
+}
+
+// name used to sort and to find letter, without keyword and leading underscore.
+fn sort_name(symbol: &Symbol) -> String {
+    let name = symbol.name.as_str();
+    let name = name
+        .strip_prefix("struct ")
+        .or_else(|| name.strip_prefix("union "))
+        .unwrap_or(name);
+    name.trim_start_matches('_').to_lowercase()
+}
+
+// group of name, non letter names are grouped under `#` listed first as Other.
+fn letter(name: &str) -> String {
+    match name.chars().next() {
+        Some(c) if c.is_alphabetic() => c.to_uppercase().to_string(),
+        _ => String::from("#"),
+    }
+}
+
+fn label(letter: String) -> String {
+    match letter.as_str() {
+        "#" => String::from("Other"),
+        _ => letter,
+    }
+}
+
+// symbols of one letter, by kind in order of KINDS.
+pub(crate) type Letter<'a> = (String, Vec<(&'static str, Vec<&'a Symbol>)>);
+
+// documented symbols of every file grouped by first letter then by kind, sorted by name.
+// symbols without description are left out.
+pub(crate) fn letters<'a>(files: &[&'a FileSymbols]) -> Vec<Letter<'a>> {
+    let mut letters: BTreeMap<String, Vec<(String, &Symbol)>> = BTreeMap::new();
+    let documented = files
+        .iter()
+        .copied()
+        .flatten()
+        .filter(|(_, s)| !s.summary.is_empty());
+    for (_, symbol) in documented {
+        let name = sort_name(symbol);
+        letters
+            .entry(letter(&name))
+            .or_default()
+            .push((name, symbol));
+    }
+    letters
+        .into_iter()
+        .map(|(l, mut symbols)| {
+            symbols.sort_by(|(a, x), (b, y)| a.cmp(b).then(x.file.cmp(&y.file)));
+            let kinds = KINDS
+                .iter()
+                .map(|k| {
+                    let list = symbols
+                        .iter()
+                        .filter(|(_, s)| kind_title(s) == *k)
+                        .map(|(_, s)| *s)
+                        .collect::<Vec<&Symbol>>();
+                    (*k, list)
+                })
+                .filter(|(_, list)| !list.is_empty())
+                .collect();
+            (label(l), kinds)
+        })
+        .collect()
+}
+
+// id of letter heading, the same as github give to `## label`.
+pub(crate) fn letter_anchor(label: &str) -> String {
+    slug(label)
+}
+
+// header of symbol shown after its summary, without leading separator.
+pub(crate) fn symbol_file(symbol: &Symbol) -> &str {
+    symbol.file.trim_start_matches(['/', '\\'])
+}
+
+// markdown index of symbols of every file. url give link of symbol seen from index page.
+pub(crate) fn index_md<F>(files: &[&FileSymbols], url: F) -> String
+where
+    F: Fn(&Symbol) -> String,
+{
+    let letters = letters(files);
+    let nav = letters
+        .iter()
+        .map(|(l, _)| format!("**[{}](#{})**", l, letter_anchor(l)))
+        .collect::<Vec<String>>()
+        .join(" · ");
+    let mut lines = vec![String::from("# Index"), String::new(), nav];
+
+    for (l, kinds) in letters {
+        lines.push(String::new());
+        lines.push(format!("## {}", l));
+        for (kind, symbols) in kinds {
+            lines.push(String::new());
+            lines.push(format!("#### {}", kind));
+            lines.push(String::new());
+            for s in symbols {
+                let summary = match s.summary.is_empty() {
+                    true => String::new(),
+                    false => format!(" — {}", s.summary),
+                };
+                lines.push(format!(
+                    "* [`{}`]({}){} *({})*",
+                    s.name,
+                    url(s),
+                    summary,
+                    symbol_file(s)
+                ));
+            }
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(name: &str, kind: DocKind, summary: &str) -> (Vec<String>, Symbol) {
+        let symbol = Symbol {
+            name: String::from(name),
+            kind,
+            page: String::from("T"),
+            anchor: name.to_lowercase().replace(' ', "-"),
+            file: String::from("/t.h"),
+            summary: String::from(summary),
+            uses: vec![],
+            deprecated: None,
+        };
+        (vec![String::from(name)], symbol)
+    }
+
+    fn names(letter: &Letter) -> Vec<(&'static str, Vec<String>)> {
+        letter
+            .1
+            .iter()
+            .map(|(k, list)| (*k, list.iter().map(|s| s.name.clone()).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn grouped_by_letter_and_kind() {
+        let files: FileSymbols = vec![
+            symbol("gx_open", DocKind::Function, "Open it."),
+            symbol("struct Gx", DocKind::Object, "A thing."),
+            symbol("_gx_close", DocKind::Function, "Close it."),
+            symbol("union Value", DocKind::Object, "A value."),
+            symbol("gx_id", DocKind::Object, "An id."),
+            symbol("_1st", DocKind::Function, "First."),
+        ];
+        let letters = letters(&[&files]);
+        let labels = letters.iter().map(|(l, _)| l.as_str()).collect::<Vec<_>>();
+        assert_eq!(labels, ["Other", "G", "V"]);
+        assert_eq!(
+            names(&letters[0]),
+            [("Functions", vec![String::from("_1st")])]
+        );
+        // leading underscore and keyword are not part of sort name
+        assert_eq!(
+            names(&letters[1]),
+            [
+                (
+                    "Functions",
+                    vec![String::from("_gx_close"), String::from("gx_open")]
+                ),
+                ("Structs", vec![String::from("struct Gx")]),
+                ("Types", vec![String::from("gx_id")]),
+            ]
+        );
+        assert_eq!(
+            names(&letters[2]),
+            [("Unions", vec![String::from("union Value")])]
+        );
+    }
+
+    #[test]
+    fn undocumented_left_out() {
+        let files: FileSymbols = vec![
+            symbol("gx_open", DocKind::Function, "Open it."),
+            symbol("_gx_internal_reset", DocKind::Function, ""),
+            symbol("struct Obj", DocKind::Object, ""),
+        ];
+        let letters = letters(&[&files]);
+        assert_eq!(letters.len(), 1);
+        assert_eq!(
+            names(&letters[0]),
+            [("Functions", vec![String::from("gx_open")])]
+        );
+    }
+
+    #[test]
+    fn index_page() {
+        let files: FileSymbols = vec![
+            symbol("gx_open", DocKind::Function, "Open it."),
+            symbol("struct Gx", DocKind::Object, "A thing."),
+            symbol("9lives", DocKind::Function, "Nine."),
+        ];
+        let md = index_md(&[&files], |s| format!("T#{}", s.anchor));
+        assert_eq!(
+            md,
+            concat!(
+                "# Index\n\n",
+                "**[Other](#other)** · **[G](#g)**\n\n",
+                "## Other\n\n",
+                "#### Functions\n\n",
+                "* [`9lives`](T#9lives) — Nine. *(t.h)*\n\n",
+                "## G\n\n",
+                "#### Functions\n\n",
+                "* [`gx_open`](T#gx_open) — Open it. *(t.h)*\n\n",
+                "#### Structs\n\n",
+                "* [`struct Gx`](T#struct-gx) — A thing. *(t.h)*\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,679 @@
+use crate::{
+    json::Json,
+    page::{
+        content::Content,
+        view::{FieldView, IntoViewAnchor, ItemRows},
+        Page,
+    },
+    symbols::{type_names, SymbolTable},
+    utils::{
+        c_function::CFunction,
+        c_includes::CIncludes,
+        c_object::{CObject, CObjectType},
+        doc_tag::{parse_tags, see_target, CommentLines},
+        AnchorMd, IntoMd, TitleMd,
+    },
+};
+
+// version of json schema written by `-format json`, see docs/ir-schema.md.
+// bump it on every change that can break consumer.
+pub(crate) const IR_VERSION: u32 = 1;
+
+// format independent documentation of whole project.
+pub(crate) struct DocProject {
+    pub(crate) files: Vec<DocFile>,
+}
+
+pub(crate) struct DocFile {
+    pub(crate) path: String,
+    pub(crate) title: String,
+    pub(crate) url: String,
+    pub(crate) main: Option<String>,
+    pub(crate) groups: Vec<DocGroup>,
+    pub(crate) items: Vec<DocItem>,
+}
+
+pub(crate) struct DocGroup {
+    pub(crate) name: String,
+    pub(crate) intro: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DocKind {
+    Object,
+    Function,
+    Include,
+}
+
+impl DocKind {
+    #[allow(clippy::wrong_self_convention)]
+    pub(crate) fn into_str(&self) -> &str {
+        match self {
+            Self::Object => "object",
+            Self::Function => "function",
+            Self::Include => "include",
+        }
+    }
+
+    pub(crate) fn from_str(str: &str) -> Option<Self> {
+        [Self::Object, Self::Function, Self::Include]
+            .into_iter()
+            .find(|x| x.into_str() == str)
+    }
+}
+
+pub(crate) struct DocItem {
+    pub(crate) kind: DocKind,
+    pub(crate) name: String,
+    pub(crate) title: String,
+    pub(crate) anchor: Option<String>,
+    pub(crate) code: String,
+    pub(crate) desc: Option<String>,
+    pub(crate) summary: Option<String>,
+    pub(crate) tags: Vec<(String, String)>,
+    pub(crate) group: Option<String>,
+    pub(crate) private: bool,
+    pub(crate) deprecated: Option<String>,
+    pub(crate) line: usize,
+    pub(crate) detail: DocDetail,
+    // documented symbols the item refers to, filled by resolve_links.
+    pub(crate) links: Vec<DocLink>,
+    // rows of item tables, the same as markdown view show.
+    pub(crate) rows: ItemRows,
+    pub(crate) comment: CommentLines,
+}
+
+// reference of an item to a documented symbol. from is `type` for type of
+// declaration, `see` for `@see` tag and `text` for [`name`] in description.
+#[derive(Clone, PartialEq, Eq)]
+pub(crate) struct DocLink {
+    pub(crate) from: String,
+    pub(crate) name: String,
+    pub(crate) page: String,
+    pub(crate) anchor: String,
+    pub(crate) file: String,
+}
+
+// name and type of function parameter or object field.
+pub(crate) struct DocVar {
+    pub(crate) name: Option<String>,
+    pub(crate) ty: String,
+    pub(crate) desc: Option<String>,
+}
+
+pub(crate) enum DocDetail {
+    Object {
+        object_type: String,
+        name: String,
+        alias: Option<String>,
+        fields: Vec<DocVar>,
+    },
+    Function {
+        returns: String,
+        params: Vec<DocVar>,
+    },
+    Include {
+        target: String,
+        url: String,
+    },
+}
+
+trait IntoDetail {
+    fn kind(&self) -> DocKind;
+    fn name(&self) -> String;
+    fn detail(&self) -> DocDetail;
+}
+
+impl IntoDetail for CObject {
+    fn kind(&self) -> DocKind {
+        DocKind::Object
+    }
+
+    fn name(&self) -> String {
+        self.get_name()
+    }
+
+    fn detail(&self) -> DocDetail {
+        DocDetail::Object {
+            object_type: match self.get_obj_type() {
+                CObjectType::Alias => String::from("typedef"),
+                x => String::from(x.into_str()),
+            },
+            name: self.get_name(),
+            alias: self.get_alias(),
+            fields: self
+                .get_fields()
+                .iter()
+                .map(|f| DocVar {
+                    name: Some(f.get_name()),
+                    ty: f.get_type(),
+                    desc: f.get_desc(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl IntoDetail for CFunction {
+    fn kind(&self) -> DocKind {
+        DocKind::Function
+    }
+
+    fn name(&self) -> String {
+        self.get_name()
+    }
+
+    fn detail(&self) -> DocDetail {
+        DocDetail::Function {
+            returns: self.get_return(),
+            params: self
+                .get_params()
+                .iter()
+                .map(|p| DocVar {
+                    name: p.get_name(),
+                    ty: p.signature(),
+                    desc: None,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl IntoDetail for CIncludes {
+    fn kind(&self) -> DocKind {
+        DocKind::Include
+    }
+
+    fn name(&self) -> String {
+        self.get_name()
+    }
+
+    fn detail(&self) -> DocDetail {
+        DocDetail::Include {
+            target: self.get_name(),
+            url: self.get_url(),
+        }
+    }
+}
+
+fn item<T>(fv: &FieldView<T>) -> DocItem
+where
+    T: IntoMd + TitleMd + AnchorMd + IntoDetail + Clone,
+    FieldView<T>: IntoViewAnchor,
+{
+    let field = fv.get_field();
+    let desc = fv.get_desc().filter(|x| !x.trim().is_empty());
+    let (summary, tags) = match desc.as_ref() {
+        Some(x) => {
+            let (text, tags) = parse_tags(x);
+            let tags = tags
+                .iter()
+                .map(|t| (t.get_tag().to_string(), t.get_text().to_string()))
+                .collect::<Vec<(String, String)>>();
+            (Some(text).filter(|x| !x.is_empty()), tags)
+        }
+        None => (None, vec![]),
+    };
+    let rows = fv.view_rows();
+    let mut detail = field.detail();
+    if let DocDetail::Function { params, .. } = &mut detail {
+        params.iter_mut().for_each(|p| {
+            p.desc = rows
+                .params
+                .iter()
+                .find(|r| Some(r.name.as_str()) == p.name.as_deref())
+                .map(|r| r.desc.clone())
+                .filter(|x| !x.is_empty());
+        });
+    }
+    let anchor = match field.kind() {
+        DocKind::Include => None,
+        _ => fv
+            .create_anchor()
+            .map(|l| l.get_url().trim_start_matches('#').to_string()),
+    };
+    DocItem {
+        kind: field.kind(),
+        name: field.name(),
+        title: field.create_title(),
+        anchor,
+        code: field
+            .into_md()
+            .lines()
+            .map(|x| x.strip_prefix('\t').unwrap_or(x))
+            .collect::<Vec<&str>>()
+            .join("\n"),
+        desc,
+        summary,
+        tags,
+        group: fv.get_group(),
+        private: fv.is_private(),
+        deprecated: fv.get_deprecated(),
+        line: fv.get_line(),
+        detail,
+        links: vec![],
+        rows,
+        comment: fv.get_comment(),
+    }
+}
+
+// names of intra-doc links like [`gx_open`] or [`gx_open()`] in text.
+fn intra_links(text: &str) -> Vec<&str> {
+    let mut names = vec![];
+    let mut rest = text;
+    while let Some(open) = rest.find("[`") {
+        let after = &rest[open + 2..];
+        let close = match after.find("`]") {
+            Some(x) => x,
+            None => break,
+        };
+        // already a markdown link or reference
+        if !after[close + 2..].starts_with(['(', '[']) {
+            names.push(after[..close].trim().trim_end_matches("()"));
+        }
+        rest = &after[close + 2..];
+    }
+    names
+}
+
+impl DocItem {
+    // names the item refers to, with where they come from.
+    fn references(&self) -> Vec<(&str, String)> {
+        let types = match &self.detail {
+            DocDetail::Object { fields, .. } => fields.iter().map(|f| f.ty.as_str()).collect(),
+            DocDetail::Function { returns, params } => [returns.as_str()]
+                .into_iter()
+                .chain(params.iter().map(|p| p.ty.as_str()))
+                .collect(),
+            DocDetail::Include { .. } => vec![],
+        };
+        let mut refs = types
+            .into_iter()
+            .flat_map(type_names)
+            .map(|x| ("type", x))
+            .collect::<Vec<(&str, String)>>();
+        refs.extend(
+            self.tags
+                .iter()
+                .filter(|(t, _)| t == "see")
+                .filter_map(|(_, x)| see_target(x))
+                .map(|x| ("see", x)),
+        );
+        if let Some(x) = self.desc.as_ref() {
+            refs.extend(
+                intra_links(x)
+                    .into_iter()
+                    .map(|x| ("text", String::from(x))),
+            );
+        }
+        refs
+    }
+}
+
+impl DocFile {
+    pub(crate) fn new(path: &str, title: &str, url: &str, content: Option<&Content>) -> Self {
+        let (main, groups, items) = match content {
+            Some(c) => {
+                let main = c
+                    .get_main()
+                    .map(|m| m.get_lines().join("\n"))
+                    .filter(|m| !m.trim().is_empty());
+                let groups = c
+                    .get_groups()
+                    .iter()
+                    .map(|g| DocGroup {
+                        name: g.get_name().to_string(),
+                        intro: g.get_intro(),
+                    })
+                    .collect::<Vec<DocGroup>>();
+                let mut items = c.get_objects().iter().map(item).collect::<Vec<DocItem>>();
+                items.extend(c.get_func().iter().map(item));
+                items.extend(c.get_include().iter().map(item));
+                (main, groups, items)
+            }
+            None => (None, vec![], vec![]),
+        };
+        DocFile {
+            path: String::from(path),
+            title: String::from(title),
+            url: String::from(url),
+            main,
+            groups,
+            items,
+        }
+    }
+}
+
+impl DocProject {
+    pub(crate) fn from_pages(pages: &[&Page]) -> Self {
+        let files = pages
+            .iter()
+            .map(|p| {
+                DocFile::new(
+                    &p.get_path_src(),
+                    &p.get_title(),
+                    &p.get_url(),
+                    p.get_content().as_ref(),
+                )
+            })
+            .collect();
+        DocProject { files }
+    }
+
+    // resolve names every item refers to with the symbol table, definition in
+    // the same header first like links of pages.
+    pub(crate) fn resolve_links(&mut self, table: &SymbolTable) {
+        for file in self.files.iter_mut() {
+            for item in file.items.iter_mut() {
+                let mut links: Vec<DocLink> = vec![];
+                for (from, name) in item.references() {
+                    let symbol = match table.get_in(&name, &file.path) {
+                        Some(x) => x,
+                        None => continue,
+                    };
+                    // type name only link to objects
+                    if from == "type" && symbol.kind != DocKind::Object {
+                        continue;
+                    }
+                    let link = DocLink {
+                        from: String::from(from),
+                        name,
+                        page: symbol.page.clone(),
+                        anchor: symbol.anchor.clone(),
+                        file: symbol.file.clone(),
+                    };
+                    if !links.contains(&link) {
+                        links.push(link);
+                    }
+                }
+                item.links = links;
+            }
+        }
+    }
+
+    pub(crate) fn to_json(&self) -> Json {
+        Json::obj(vec![
+            ("schema_version", Json::Num(IR_VERSION as f64)),
+            ("generator", Json::str(env!("CARGO_PKG_NAME"))),
+            (
+                "files",
+                Json::Arr(self.files.iter().map(|f| f.to_json()).collect()),
+            ),
+        ])
+    }
+}
+
+fn opt(str: &Option<String>) -> Json {
+    match str {
+        Some(x) => Json::str(x),
+        None => Json::Null,
+    }
+}
+
+fn vars(vars: &[DocVar]) -> Json {
+    Json::Arr(
+        vars.iter()
+            .map(|v| {
+                Json::obj(vec![
+                    ("name", opt(&v.name)),
+                    ("type", Json::str(&v.ty)),
+                    ("desc", opt(&v.desc)),
+                ])
+            })
+            .collect(),
+    )
+}
+
+impl DocFile {
+    fn to_json(&self) -> Json {
+        Json::obj(vec![
+            ("path", Json::str(&self.path)),
+            ("title", Json::str(&self.title)),
+            ("url", Json::str(&self.url)),
+            ("description", opt(&self.main)),
+            (
+                "groups",
+                Json::Arr(
+                    self.groups
+                        .iter()
+                        .map(|g| {
+                            Json::obj(vec![("name", Json::str(&g.name)), ("intro", opt(&g.intro))])
+                        })
+                        .collect(),
+                ),
+            ),
+            (
+                "items",
+                Json::Arr(self.items.iter().map(|i| i.to_json(&self.path)).collect()),
+            ),
+        ])
+    }
+}
+
+impl DocItem {
+    fn to_json(&self, path: &str) -> Json {
+        let detail = match &self.detail {
+            DocDetail::Object {
+                object_type,
+                name,
+                alias,
+                fields,
+            } => Json::obj(vec![
+                ("object_type", Json::str(object_type)),
+                ("name", Json::str(name)),
+                ("alias", opt(alias)),
+                ("fields", vars(fields)),
+            ]),
+            DocDetail::Function { returns, params } => Json::obj(vec![
+                ("returns", Json::str(returns)),
+                ("params", vars(params)),
+            ]),
+            DocDetail::Include { target, url } => {
+                Json::obj(vec![("target", Json::str(target)), ("url", Json::str(url))])
+            }
+        };
+        Json::obj(vec![
+            ("kind", Json::str(self.kind.into_str())),
+            ("name", Json::str(&self.name)),
+            ("title", Json::str(&self.title)),
+            ("anchor", opt(&self.anchor)),
+            ("code", Json::str(&self.code)),
+            ("description", opt(&self.desc)),
+            ("summary", opt(&self.summary)),
+            (
+                "tags",
+                Json::Arr(
+                    self.tags
+                        .iter()
+                        .map(|(t, x)| {
+                            Json::obj(vec![("tag", Json::str(t)), ("text", Json::str(x))])
+                        })
+                        .collect(),
+                ),
+            ),
+            ("group", opt(&self.group)),
+            ("private", Json::Bool(self.private)),
+            ("deprecated", opt(&self.deprecated)),
+            (
+                "span",
+                Json::obj(vec![
+                    ("file", Json::str(path)),
+                    ("line", Json::Num(self.line as f64)),
+                ]),
+            ),
+            ("detail", detail),
+            (
+                "links",
+                Json::Arr(
+                    self.links
+                        .iter()
+                        .map(|l| {
+                            Json::obj(vec![
+                                ("from", Json::str(&l.from)),
+                                ("name", Json::str(&l.name)),
+                                ("page", Json::str(&l.page)),
+                                ("anchor", Json::str(&l.anchor)),
+                                ("file", Json::str(&l.file)),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, io::BufRead};
+
+    use super::*;
+    use crate::{config::Config, parser::str_parser, utils::c_impl::CImplDocs};
+
+    const SCHEMA: &str = include_str!("../docs/ir-schema.md");
+
+    fn page(path: &str, url: &str, text: &str) -> Page {
+        let args = ["gx_md", "-src", "src", "-o", "out", "-h", "t.h"];
+        let cfg = Config::from_args(args.iter().map(|x| x.to_string()).collect()).unwrap();
+        let mut page = Page::new();
+        page.set_content(Some(str_parser(
+            text.as_bytes().lines(),
+            &cfg,
+            &CImplDocs::new(),
+        )));
+        page.set_path_src(path);
+        page.set_url(url);
+        page.set_title(path.trim_start_matches('/'));
+        page
+    }
+
+    fn project() -> Json {
+        let t = page(
+            "/t.h",
+            "T",
+            concat!(
+                "#include \"u.h\"\n",
+                "\n",
+                "//! a point\n",
+                "struct Pt {\n",
+                "    int x; //!< x value\n",
+                "};\n",
+                "\n",
+                "//! move [`pt`] like [`gx_free()`], see [`nope`]\n",
+                "//! @param pt point\n",
+                "//! @see gx_free\n",
+                "void gx_move(struct Pt *pt, Buf *b);\n",
+            ),
+        );
+        let u = page(
+            "/u.h",
+            "U",
+            "//! a buffer\ntypedef struct {\n    int n;\n} Buf;\n\n//! free it\nvoid gx_free(Buf *b);\n",
+        );
+        let mut project = DocProject::from_pages(&[&t, &u]);
+        let symbols = project
+            .files
+            .iter()
+            .map(|f| SymbolTable::file_symbols(f, false))
+            .collect::<Vec<_>>();
+        project.resolve_links(&SymbolTable::from_symbols(&symbols));
+        project.to_json()
+    }
+
+    // field names of every table of schema doc, by heading above the table.
+    fn schema_fields() -> HashMap<String, Vec<String>> {
+        let mut tables: HashMap<String, Vec<String>> = HashMap::new();
+        let mut heading = String::new();
+        for line in SCHEMA.lines() {
+            if let Some(x) = line.strip_prefix("### ") {
+                heading = String::from(x);
+            } else if line.starts_with('`') && line.ends_with(':') {
+                heading = String::from(line);
+            } else if let Some(x) = line.strip_prefix("| `") {
+                let name = x.split('`').next().unwrap();
+                tables
+                    .entry(heading.clone())
+                    .or_default()
+                    .push(String::from(name));
+            }
+        }
+        tables
+    }
+
+    fn keys(json: &Json) -> Vec<String> {
+        match json {
+            Json::Obj(x) => x.iter().map(|(k, _)| k.clone()).collect(),
+            _ => vec![],
+        }
+    }
+
+    fn at(json: &Json, i: usize) -> &Json {
+        match json {
+            Json::Arr(x) => &x[i],
+            _ => panic!("not an array"),
+        }
+    }
+
+    fn str(json: Option<&Json>) -> &str {
+        match json {
+            Some(Json::Str(x)) => x,
+            _ => panic!("not a string"),
+        }
+    }
+
+    #[test]
+    fn json_match_schema() {
+        let json = project();
+        let schema = schema_fields();
+        assert!(
+            matches!(json.get("schema_version"), Some(Json::Num(x)) if *x == IR_VERSION as f64)
+        );
+
+        let file = at(json.get("files").unwrap(), 0);
+        let items = file.get("items").unwrap();
+        let (object, function, include) = (at(items, 0), at(items, 1), at(items, 2));
+        assert_eq!(str(object.get("kind")), "object");
+        assert_eq!(str(function.get("kind")), "function");
+        assert_eq!(str(include.get("kind")), "include");
+
+        for (json, table) in [
+            (&json, "Project"),
+            (file, "File"),
+            (object, "Item"),
+            (function, "Item"),
+            (object.get("detail").unwrap(), "`detail` of an object:"),
+            (function.get("detail").unwrap(), "`detail` of a function:"),
+            (include.get("detail").unwrap(), "`detail` of an include:"),
+            (at(function.get("links").unwrap(), 0), "Link"),
+        ] {
+            assert_eq!(keys(json), schema[table], "{}", table);
+        }
+    }
+
+    #[test]
+    fn resolved_links() {
+        let json = project();
+        let function = at(at(json.get("files").unwrap(), 0).get("items").unwrap(), 1);
+        let links = match function.get("links") {
+            Some(Json::Arr(x)) => x
+                .iter()
+                .map(|l| {
+                    ["from", "name", "page", "anchor", "file"]
+                        .map(|k| str(l.get(k)))
+                        .join(" ")
+                })
+                .collect::<Vec<String>>(),
+            _ => panic!("no links"),
+        };
+        // [`pt`] is a parameter and [`nope`] match nothing
+        assert_eq!(
+            links,
+            [
+                "type struct Pt T struct-pt /t.h",
+                "type Buf U buf /u.h",
+                "see gx_free U gx_free /u.h",
+                "text gx_free U gx_free /u.h",
+            ]
+        );
+    }
+}
@@ -0,0 +1,309 @@
+use crate::{
+    ir::DocFile,
+    page::content::Content,
+    symbols::SymbolTable,
+    utils::doc_tag::{parse_tags, see_target},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LintLevel {
+    Off,
+    Warn,
+    Error,
+}
+
+impl LintLevel {
+    pub(crate) fn from_str(str: &str) -> Option<Self> {
+        match str {
+            "off" => Some(Self::Off),
+            "warn" => Some(Self::Warn),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    pub(crate) fn into_str(&self) -> &str {
+        match self {
+            Self::Off => "off",
+            Self::Warn => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+// check `@param`, `@return` and `@see` tags against parsed declarations.
+// return number of reported problems.
+pub(crate) fn lint(files: &[(String, Content)], level: LintLevel, include_private: bool) -> usize {
+    if level == LintLevel::Off {
+        return 0;
+    }
+    let reports = problems(files, include_private);
+    reports.iter().for_each(|(file, line, msg)| {
+        eprintln!("{}:{}: {}: {}", file, line, level.into_str(), msg)
+    });
+    reports.len()
+}
+
+// file, line of the tag and message of every problem, sorted by file and line.
+fn problems(files: &[(String, Content)], include_private: bool) -> Vec<(&str, usize, String)> {
+    // `@see` target are the symbols pages link to
+    let symbols = files
+        .iter()
+        .map(|(file, content)| {
+            let title = file.rsplit(['/', '\\']).next().unwrap_or(file);
+            let doc = DocFile::new(file, title, title, Some(content));
+            SymbolTable::file_symbols(&doc, include_private)
+        })
+        .collect::<Vec<_>>();
+    let table = SymbolTable::from_symbols(&symbols);
+
+    let mut reports: Vec<(&str, usize, String)> = vec![];
+    for (file, content) in files {
+        let file = file.as_str();
+        for f in content.get_func() {
+            if f.is_private() && !include_private {
+                continue;
+            }
+            let desc = match f.get_desc() {
+                Some(x) => x,
+                None => continue,
+            };
+            let func = f.get_field();
+            let (_, tags) = parse_tags(&desc);
+            let params = func
+                .get_params()
+                .iter()
+                .filter(|p| p.get_unit() != "void" || p.get_name().is_some())
+                .filter_map(|p| p.get_name())
+                .collect::<Vec<String>>();
+
+            let documented = tags
+                .iter()
+                .filter(|t| t.get_tag() == "param")
+                .filter_map(|t| t.get_arg())
+                .collect::<Vec<&str>>();
+
+            documented
+                .iter()
+                .filter(|d| !params.iter().any(|p| p == *d))
+                .for_each(|d| {
+                    reports.push((
+                        file,
+                        f.line_of(&format!("@param {}", d)),
+                        format!("`@param {}` is not a parameter of {}.", d, func.get_name()),
+                    ))
+                });
+
+            params
+                .iter()
+                .filter(|p| !documented.contains(&p.as_str()))
+                .for_each(|p| {
+                    reports.push((
+                        file,
+                        f.get_line(),
+                        format!("parameter {} of {} has no `@param`.", p, func.get_name()),
+                    ))
+                });
+
+            let returns = tags
+                .iter()
+                .find(|t| t.get_tag() == "return" || t.get_tag() == "returns");
+            if let (true, Some(t)) = (func.is_void(), returns) {
+                let tag = format!("@{}", t.get_tag());
+                reports.push((
+                    file,
+                    f.line_of(&tag),
+                    format!("`{}` on void function {}.", tag, func.get_name()),
+                ));
+            }
+        }
+
+        let mut sees: Vec<(String, usize)> = vec![];
+        let mut see = |private: bool, desc: Option<String>, line: &dyn Fn(&str) -> usize| {
+            if private && !include_private {
+                return;
+            }
+            if let Some(d) = desc {
+                let (_, tags) = parse_tags(&d);
+                tags.iter()
+                    .filter(|t| t.get_tag() == "see")
+                    .filter_map(|t| see_target(t.get_text()))
+                    .filter(|t| table.get_in(t, file).is_none())
+                    .for_each(|t| {
+                        let word = t.split_whitespace().next().unwrap_or_default();
+                        sees.push((t.clone(), line(&format!("@see {}", word))));
+                    });
+            }
+        };
+        content
+            .get_objects()
+            .iter()
+            .for_each(|x| see(x.is_private(), x.get_desc(), &|t| x.line_of(t)));
+        content
+            .get_func()
+            .iter()
+            .for_each(|x| see(x.is_private(), x.get_desc(), &|t| x.line_of(t)));
+        content
+            .get_include()
+            .iter()
+            .for_each(|x| see(x.is_private(), x.get_desc(), &|t| x.line_of(t)));
+
+        sees.into_iter().for_each(|(t, line)| {
+            reports.push((file, line, format!("`@see {}` match no symbol.", t)))
+        });
+    }
+
+    // stable sort keep order of problems on the same line
+    reports.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+    reports
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufRead;
+
+    use super::*;
+    use crate::{config::Config, parser::str_parser, utils::c_impl::CImplDocs};
+
+    fn lint_text(text: &str, level: LintLevel) -> usize {
+        let args = ["gx_md", "-src", "src", "-o", "out", "-h", "t.h"];
+        let cfg = Config::from_args(args.iter().map(|x| x.to_string()).collect()).unwrap();
+        let content = str_parser(text.as_bytes().lines(), &cfg, &CImplDocs::new());
+        lint(&[(String::from("/t.h"), content)], level, false)
+    }
+
+    fn count(text: &str) -> usize {
+        lint_text(text, LintLevel::Warn)
+    }
+
+    // line and message of problems of every file
+    fn reports(files: &[(&str, &str)], include_private: bool) -> Vec<(String, usize)> {
+        let args = ["gx_md", "-src", "src", "-o", "out", "-h", "t.h"];
+        let cfg = Config::from_args(args.iter().map(|x| x.to_string()).collect()).unwrap();
+        let files = files
+            .iter()
+            .map(|(f, text)| {
+                let content = str_parser(text.as_bytes().lines(), &cfg, &CImplDocs::new());
+                (String::from(*f), content)
+            })
+            .collect::<Vec<_>>();
+        problems(&files, include_private)
+            .into_iter()
+            .map(|(f, l, _)| (String::from(f), l))
+            .collect()
+    }
+
+    #[test]
+    fn unknown_param() {
+        assert_eq!(
+            count("//! open\n//! @param fd file\n//! @param nope x\nint open(int fd);\n"),
+            1
+        );
+        assert_eq!(
+            count("//! open\n//! @param fd file\nint open(int fd);\n"),
+            0
+        );
+    }
+
+    #[test]
+    fn missing_param() {
+        assert_eq!(count("//! open\nint open(int fd, int flags);\n"), 2);
+        assert_eq!(
+            count("//! open\n//! @param fd file\nint open(int fd, int flags);\n"),
+            1
+        );
+        assert_eq!(count("//! reset\nvoid reset(void);\n"), 0);
+        // undocumented function is left to coverage
+        assert_eq!(count("int open(int fd);\n"), 0);
+    }
+
+    #[test]
+    fn return_on_void() {
+        assert_eq!(
+            count("//! reset\n//! @return nothing\nvoid reset(void);\n"),
+            1
+        );
+        assert_eq!(count("//! size\n//! @return bytes\nint size(void);\n"), 0);
+        assert_eq!(
+            count("//! reset\n//! @returns nothing\nvoid reset(void);\n"),
+            1
+        );
+        assert_eq!(count("//! size\n//! @returns bytes\nint size(void);\n"), 0);
+    }
+
+    #[test]
+    fn see_unknown_symbol() {
+        let text =
+            "//! a thing\nstruct Obj {\n    int a;\n};\n\n//! size\n//! @see {}\nint size(void);\n";
+        assert_eq!(count(&text.replace("{}", "gx_missing()")), 1);
+        assert_eq!(count(&text.replace("{}", "struct Obj")), 0);
+        assert_eq!(count(&text.replace("{}", "size().")), 0);
+    }
+
+    #[test]
+    fn level_off() {
+        assert_eq!(
+            lint_text("//! open\nint open(int fd);\n", LintLevel::Off),
+            0
+        );
+        assert_eq!(
+            lint_text("//! open\nint open(int fd);\n", LintLevel::Error),
+            1
+        );
+    }
+
+    #[test]
+    fn private_items() {
+        let text = concat!(
+            "//! hidden\n",
+            "//! @private\n",
+            "int hidden(int fd);\n",
+            "\n",
+            "//! size\n",
+            "//! @see hidden()\n",
+            "int size(void);\n",
+        );
+        // problem of private item is not counted, nor is it a `@see` target
+        assert_eq!(
+            reports(&[("/t.h", text)], false),
+            [(String::from("/t.h"), 6)]
+        );
+        assert_eq!(
+            reports(&[("/t.h", text)], true),
+            [(String::from("/t.h"), 3)]
+        );
+    }
+
+    #[test]
+    fn tag_line_and_order() {
+        let b = concat!(
+            "//! open\n",
+            "//!\n",
+            "//! @param fd file\n",
+            "//! @param nope x\n",
+            "//! @return status\n",
+            "void open(int fd);\n",
+            "\n",
+            "//! size\n",
+            "//! @see gx_missing()\n",
+            "int size(void);\n",
+        );
+        let a = "//! close\n//! @param nope x\nint close(int fd);\n";
+        let found = reports(&[("/b.h", b), ("/a.h", a)], false);
+        let expected = [
+            ("/a.h", 2),
+            ("/a.h", 3),
+            ("/b.h", 4),
+            ("/b.h", 5),
+            ("/b.h", 9),
+        ];
+        assert_eq!(
+            found,
+            expected
+                .iter()
+                .map(|(f, l)| (String::from(*f), *l))
+                .collect::<Vec<_>>()
+        );
+    }
+}
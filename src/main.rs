@@ -1,95 +1,565 @@
-use std::{env, process::exit};
+use std::{
+    collections::HashSet,
+    env,
+    fs::{create_dir_all, read, read_to_string, write},
+    path::Path,
+    process::exit,
+    sync::Arc,
+};
 
-use file_reader::file_list;
-use file_writer::{parse_into_file, GxFile};
+use cache::{hash, Cache, CacheEntry};
+use config::{Config, Diagram, Format, Graph, Mode, Sidebar, USAGE};
+use coverage::{Coverage, FileCoverage};
+use deprecated::{deprecated_md, DEPRECATED_PAGE};
+use diagram::class_diagram;
+use error::Error;
+use file_reader::{file_list, read_line};
+use file_writer::{parse_into_page, write_file, GxFile, PageFile};
+use graph::{includes, IncludeGraph, DOT_FILE};
+use html::write_html;
+use index::{index_md, INDEX_PAGE};
+use ir::DocProject;
+use lint::{lint, LintLevel};
+use man::write_man;
+use mdbook::write_mdbook;
+use page::{navigation::global_side_bar, Page};
+use parser::{impl_parser, str_parser};
+use single::write_single;
+use symbols::{relative_url, tokens, FileSymbols, Linker, SymbolTable};
+use template::Templates;
+use utils::c_impl::CImplDocs;
+use watch::watch;
+use worker::parallel_map;
 
+mod cache;
+mod config;
+mod coverage;
+mod deprecated;
+mod diagram;
+mod error;
 mod file_reader;
 mod file_writer;
+mod graph;
+mod html;
+mod index;
+mod ir;
+mod json;
+mod lint;
+mod man;
+mod mdbook;
 mod page;
 mod parser;
+mod single;
+mod slug;
+mod symbols;
+mod template;
 mod utils;
-
-enum Command {
-    Src,
-    OutDir,
-    Home,
-    Name,
-}
-
-impl Command {
-    pub(crate) fn into_str(&self) -> &str {
-        match self {
-            Self::Src => "-src",
-            Self::OutDir => "-o",
-            Self::Home => "-h",
-            Self::Name => "gx_md",
-        }
-    }
-}
+mod watch;
+mod worker;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
 
-    if args.len() <= 5 {
+    let cfg = match Config::from_args(args) {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("Error: {}.", e);
+            println!("{}", USAGE);
+            exit(1)
+        }
+    };
+    if !cfg.is_valid() {
         eprintln!("No argument provided.");
-        println!("Usage: gx_md -src [source dir] -o [output directory] -h [home_src_file.h]");
+        println!("{}", USAGE);
         exit(1);
     }
 
-    let mut prev: Command = Command::Name;
+    let watching = cfg.watch && cfg.mode == Mode::Generate;
+    if let Err(e) = generate(&cfg) {
+        eprintln!("{}", e);
+        // watch mode wait for sources to be fixed
+        if !watching {
+            exit(e.code);
+        }
+    }
+    if watching {
+        watch(&cfg, || generate(&cfg));
+    }
+}
 
-    let (src, out, home) = {
-        let mut src = String::new();
-        let mut out = String::new();
-        let mut home = String::new();
-        for arg in args {
-            if arg == Command::Src.into_str() {
-                prev = Command::Src;
-                continue;
-            } else if arg == Command::OutDir.into_str() {
-                prev = Command::OutDir;
-                continue;
-            } else if arg == Command::Name.into_str() {
-                prev = Command::Name;
-                continue;
-            } else if arg == Command::Home.into_str() {
-                prev = Command::Home;
-                continue;
+fn generate(cfg: &Config) -> Result<(), Error> {
+    let src = &cfg.src;
+    // keep stdout clean when it carry json report
+    let log = |msg: String| {
+        if !cfg.json {
+            println!("{}", msg);
+        }
+    };
+
+    let mut srcs: Vec<String> = vec![];
+    log(format!(":> List all files in directory {}.", src));
+    file_list(&mut srcs, src, ".h").map_err(|e| Error::new(3, format!("Error: {}", e)))?;
+    // output must not depend on directory listing order
+    srcs.sort();
+
+    let mut impls = CImplDocs::new();
+    // content of .c files is part of header pages when merged
+    let mut impl_hash: u64 = 0;
+    if cfg.merge_impl {
+        let mut impl_srcs: Vec<String> = vec![];
+        log(format!(
+            ":> List all implementation files in directory {}.",
+            src
+        ));
+        file_list(&mut impl_srcs, src, ".c").map_err(|e| Error::new(3, format!("Error: {}", e)))?;
+        impl_srcs.sort();
+        for s in impl_srcs {
+            if let Ok(x) = read(&s) {
+                impl_hash = hash(format!("{:016x} {} {:016x}", impl_hash, s, hash(&x)).as_bytes());
             }
-            match prev {
-                Command::Src => {
-                    src = arg;
-                }
-                Command::OutDir => {
-                    out = arg;
-                }
-                Command::Name => {}
-                Command::Home => {
-                    home = arg;
-                }
+            match read_line(&s) {
+                Ok(x) => impl_parser(x, &s, &mut impls),
+                Err(e) => eprintln!(
+                    "Reading implementation file: {} is failed.\nError: {}.",
+                    &s, e
+                ),
             }
         }
-        (src, out, home)
+        log(format!(":> Found {} documented definitions.", impls.len()));
+    }
+
+    if cfg.mode == Mode::Check {
+        return check_coverage(cfg, srcs, &impls);
+    }
+
+    match cfg.format {
+        Format::Json => return write_json(cfg, srcs, &impls),
+        Format::Html => {
+            let pages = parse_graph_pages(cfg, srcs, &impls)?;
+            return write_html(cfg, pages);
+        }
+        Format::MdBook => {
+            let templates = load_templates(cfg)?;
+            let pages = parse_graph_pages(cfg, srcs, &impls)?;
+            return write_mdbook(cfg, pages, templates);
+        }
+        Format::Single => {
+            let templates = load_templates(cfg)?;
+            let pages = parse_graph_pages(cfg, srcs, &impls)?;
+            return write_single(cfg, pages, templates);
+        }
+        Format::Man => return write_man(cfg, parse_pages(cfg, srcs, &impls)?),
+        Format::Markdown => {}
+    }
+
+    let templates = load_templates(cfg)?;
+    let key = hash(
+        format!(
+            "{} {:016x} {:016x}",
+            cfg.fingerprint(),
+            impl_hash,
+            templates.get_hash()
+        )
+        .as_bytes(),
+    );
+    write_markdown(cfg, srcs, &impls, templates, key)
+}
+
+fn load_templates(cfg: &Config) -> Result<Arc<Templates>, Error> {
+    match Templates::load(cfg.template_dir.as_deref()) {
+        Ok(x) => Ok(Arc::new(x)),
+        Err(e) => Err(Error::failed(12, "Loading templates", e)),
+    }
+}
+
+fn read_error(s: &str, e: std::io::Error) -> Error {
+    Error::failed(2, &format!("Reading file: {}", s), e)
+}
+
+fn parse_page(cfg: &Config, s: &String, impls: &CImplDocs) -> Result<PageFile, Error> {
+    println!(":> Start Documenting file: {}.", &s);
+    let indexed_file = GxFile {
+        dir: cfg.src.clone(),
+        file: s.clone(),
+        out_dir: cfg.out.clone(),
+        home_file: cfg.home.clone(),
     };
+    parse_into_page(&indexed_file, cfg, impls)
+}
 
-    let mut srcs: Vec<String> = vec![];
-    println!(":> List all files in directory {}.", src);
-    file_list(&mut srcs, &src);
-
-    for s in srcs {
-        println!(":> Start Documenting file: {}.", &s);
-        let indexed_file = GxFile {
-            dir: src.clone(),
-            file: s.clone(),
-            out_dir: out.clone(),
-            home_file: home.clone(),
+fn parse_pages(cfg: &Config, srcs: Vec<String>, impls: &CImplDocs) -> Result<Vec<PageFile>, Error> {
+    parallel_map(&srcs, cfg.jobs, |s| parse_page(cfg, s, impls))
+        .into_iter()
+        .collect()
+}
+
+// pages with their include graph, dot file of the graph is written.
+fn parse_graph_pages(
+    cfg: &Config,
+    srcs: Vec<String>,
+    impls: &CImplDocs,
+) -> Result<Vec<PageFile>, Error> {
+    let texts = srcs
+        .iter()
+        .map(|s| match read_to_string(s) {
+            Ok(x) => Ok((s.clone(), x)),
+            Err(e) => Err(read_error(s, e)),
+        })
+        .collect::<Result<Vec<(String, String)>, Error>>()?;
+    let graph = include_graph(cfg, &texts);
+    let mut pages = parse_pages(cfg, srcs, impls)?;
+    if let Some(x) = graph.as_ref() {
+        pages.iter_mut().for_each(|pf| {
+            let md = page_graph(cfg, x, &pf.page.get_path_src());
+            pf.page.set_include_graph(md);
+        });
+        write_dot(cfg, x)?;
+    }
+    project_diagram(
+        cfg,
+        &mut pages.iter_mut().map(|x| &mut x.page).collect::<Vec<_>>(),
+    );
+    Ok(pages)
+}
+
+// include graph of every header, None when disabled. circular includes are reported.
+fn include_graph<S>(cfg: &Config, sources: &[(String, S)]) -> Option<IncludeGraph>
+where
+    S: AsRef<str>,
+{
+    if cfg.include_graph == Graph::Off {
+        return None;
+    }
+    let files = sources
+        .iter()
+        .map(|(s, text)| (String::from(source_path(cfg, s)), includes(text.as_ref())))
+        .collect::<Vec<(String, Vec<String>)>>();
+    let graph = IncludeGraph::new(&files);
+    for cycle in graph.cycles() {
+        eprintln!("::> Warning: circular include {}.", cycle.join(" -> "));
+    }
+    Some(graph)
+}
+
+// path of header from source directory, e.g. `/sub/t.h`, the same as page path.
+fn source_path<'a>(cfg: &Config, s: &'a str) -> &'a str {
+    s.strip_prefix(cfg.src.trim_end_matches(['/', '\\']))
+        .unwrap_or(s)
+}
+
+fn is_home(cfg: &Config, path: &str) -> bool {
+    path.trim_start_matches(['/', '\\']) == cfg.home.trim_start_matches(['/', '\\'])
+}
+
+// whole graph on home page, neighbourhood of header on other pages when asked.
+fn page_graph(cfg: &Config, graph: &IncludeGraph, path: &str) -> Option<String> {
+    match is_home(cfg, path) {
+        true => graph.mermaid(),
+        false if cfg.include_graph == Graph::Pages => graph.neighbourhood(path),
+        false => None,
+    }
+}
+
+// home page show structs of every page when project diagram is asked.
+fn project_diagram(cfg: &Config, pages: &mut [&mut Page]) {
+    if cfg.class_diagram != Diagram::Project {
+        return;
+    }
+    let objects = pages
+        .iter()
+        .filter_map(|p| p.get_content())
+        .flat_map(|c| c.visible_objects())
+        .collect::<Vec<_>>();
+    let diagram = class_diagram(&objects, |_| None);
+    pages
+        .iter_mut()
+        .filter(|p| is_home(cfg, &p.get_path_src()))
+        .for_each(|p| p.set_class_diagram(diagram.clone()));
+}
+
+// write graphviz file of graph, return number of files written.
+fn write_dot(cfg: &Config, graph: &IncludeGraph) -> Result<usize, Error> {
+    let out = Path::new(&cfg.out).join(DOT_FILE);
+    let dot = graph.dot();
+    if read_to_string(&out).ok().as_ref() == Some(&dot) {
+        return Ok(0);
+    }
+    write_file(&out.to_string_lossy(), &dot)
+        .map_err(|e| Error::failed(7, "Writing include graph", e))?;
+    Ok(1)
+}
+
+fn file_symbols(cfg: &Config, pf: &PageFile) -> FileSymbols {
+    let project = DocProject::from_pages(&[&pf.page]);
+    SymbolTable::file_symbols(&project.files[0], cfg.include_private)
+}
+
+fn write_markdown(
+    cfg: &Config,
+    srcs: Vec<String>,
+    impls: &CImplDocs,
+    templates: Arc<Templates>,
+    key: u64,
+) -> Result<(), Error> {
+    let old = Cache::load(&cfg.out, key);
+    let sources = parallel_map(&srcs, cfg.jobs, |s| match read_to_string(s) {
+        Ok(x) => Ok((s.clone(), hash(x.as_bytes()), x)),
+        Err(e) => Err(read_error(s, e)),
+    })
+    .into_iter()
+    .collect::<Result<Vec<_>, Error>>()?;
+
+    let texts = sources
+        .iter()
+        .map(|(s, _, text)| (s.clone(), text.as_str()))
+        .collect::<Vec<_>>();
+    let graph = include_graph(cfg, &texts);
+    let graphs = texts
+        .iter()
+        .map(|(s, _)| {
+            graph
+                .as_ref()
+                .and_then(|g| page_graph(cfg, g, source_path(cfg, s)))
+        })
+        .collect::<Vec<Option<String>>>();
+
+    // header not changed since last run is not parsed again, project diagram need
+    // structs of every header
+    let items = sources.iter().zip(&graphs).collect::<Vec<_>>();
+    let reuse = cfg.class_diagram != Diagram::Project;
+    let mut pages = parallel_map(&items, cfg.jobs, |((s, h, _), g)| match old.get(s) {
+        Some(e) if reuse && e.is_fresh(*h, hash(g.as_deref().unwrap_or_default().as_bytes())) => {
+            Ok(None)
+        }
+        _ => parse_page(cfg, s, impls).map(Some),
+    })
+    .into_iter()
+    .collect::<Result<Vec<Option<PageFile>>, Error>>()?;
+
+    println!(":> Building symbol table.");
+    let symbols = sources
+        .iter()
+        .zip(&pages)
+        .map(|((s, _, _), pf)| match pf {
+            Some(x) => file_symbols(cfg, x),
+            None => old.get(s).unwrap().symbols.clone(),
+        })
+        .collect::<Vec<FileSymbols>>();
+    let table = Arc::new(SymbolTable::from_symbols(&symbols));
+
+    // unchanged header that mention a moved, added or removed symbol can have new links
+    let changed = old.changed_names(&symbols);
+    let changed = changed
+        .iter()
+        .filter_map(|n| n.rsplit(' ').next())
+        .collect::<HashSet<&str>>();
+    let dependents = sources
+        .iter()
+        .enumerate()
+        .filter(|(i, (_, _, text))| {
+            pages[*i].is_none()
+                && tokens(text)
+                    .iter()
+                    .any(|(is_ident, t)| *is_ident && changed.contains(t))
+        })
+        .map(|(i, _)| i)
+        .collect::<Vec<usize>>();
+    let parsed = parallel_map(&dependents, cfg.jobs, |i| {
+        parse_page(cfg, &sources[*i].0, impls)
+    })
+    .into_iter()
+    .collect::<Result<Vec<PageFile>, Error>>()?;
+    dependents
+        .into_iter()
+        .zip(parsed)
+        .for_each(|(i, pf)| pages[i] = Some(pf));
+
+    pages.iter_mut().zip(&graphs).for_each(|(pf, g)| {
+        let pf = match pf {
+            Some(x) => x,
+            None => return,
         };
-        match parse_into_file(&indexed_file) {
-            Ok(_) => println!("::> Documentation for file {} : Success.", &s),
-            Err(e) => {
-                eprintln!("Documentation file: {} is failed.\nError: {}.", &s, e);
-                exit(7)
+        pf.page.set_include_graph(g.clone());
+        let linker = Linker::new(table.clone(), &pf.page.get_url(), &pf.page.get_path_src());
+        pf.page.set_linker(Some(linker));
+        pf.page.set_templates(templates.clone());
+    });
+
+    project_diagram(
+        cfg,
+        &mut pages
+            .iter_mut()
+            .flatten()
+            .map(|x| &mut x.page)
+            .collect::<Vec<_>>(),
+    );
+
+    let items = sources.iter().zip(symbols).zip(&pages).collect::<Vec<_>>();
+    let entries = parallel_map(&items, cfg.jobs, |(((s, h, _), symbols), pf)| {
+        let pf = match pf {
+            Some(x) => x,
+            None => return Ok((old.get(s).unwrap().clone(), 0)),
+        };
+        let src = pf.page.get_path_src();
+        println!(":> Writing documentation of file: {}.", &src);
+        let page = pf.page.render_content();
+        let side = pf.page.render_side_bar().unwrap();
+        let entry = CacheEntry {
+            path: src.clone(),
+            url: pf.page.get_url(),
+            src: *h,
+            out_page: pf.out_page.clone(),
+            page: hash(page.as_bytes()),
+            out_side: pf.out_side.clone(),
+            side: hash(side.as_bytes()),
+            graph: hash(pf.page.get_include_graph().unwrap_or_default().as_bytes()),
+            symbols: symbols.clone(),
+        };
+        // page with the same output is not written again
+        let prev = old.get(s);
+        let mut written = 0;
+        for (out, content, h, prev) in [
+            (
+                &entry.out_page,
+                &page,
+                entry.page,
+                prev.map(|p| (&p.out_page, p.page)),
+            ),
+            (
+                &entry.out_side,
+                &side,
+                entry.side,
+                prev.map(|p| (&p.out_side, p.side)),
+            ),
+        ] {
+            if out.is_empty() || prev == Some((out, h)) && Path::new(out).exists() {
+                continue;
+            }
+            if let Err(e) = write_file(out, content) {
+                let what = format!("Documentation file: {}", &src);
+                return Err(Error::failed(7, &what, e));
             }
+            written += 1;
         }
+        match written {
+            0 => println!("::> Documentation for file {} : Unchanged.", &src),
+            _ => println!("::> Documentation for file {} : Success.", &src),
+        }
+        Ok((entry, written))
+    })
+    .into_iter()
+    .collect::<Result<Vec<(CacheEntry, usize)>, Error>>()?;
+
+    let mut written = entries.iter().map(|(_, n)| n).sum::<usize>();
+    if cfg.sidebar == Sidebar::Global {
+        let files = entries
+            .iter()
+            .map(|(e, _)| (e.path.as_str(), e.url.as_str(), &e.symbols))
+            .collect::<Vec<_>>();
+        let out = Path::new(&cfg.out).join("_Sidebar.md");
+        let side = global_side_bar(&files);
+        // like pages, sidebar is only written when it changed
+        if read_to_string(&out).ok().as_ref() != Some(&side) {
+            write_file(&out.to_string_lossy(), &side)
+                .map_err(|e| Error::failed(7, "Writing sidebar", e))?;
+            written += 1;
+        }
+    }
+
+    if let Some(x) = graph.as_ref() {
+        written += write_dot(cfg, x)?;
+    }
+
+    let files = entries.iter().map(|(e, _)| &e.symbols).collect::<Vec<_>>();
+    let out = Path::new(&cfg.out).join(format!("{}.md", INDEX_PAGE));
+    let index = index_md(&files, |s| {
+        format!("{}#{}", relative_url(INDEX_PAGE, &s.page), s.anchor)
+    });
+    if read_to_string(&out).ok().as_ref() != Some(&index) {
+        write_file(&out.to_string_lossy(), &index)
+            .map_err(|e| Error::failed(7, "Writing symbol index", e))?;
+        written += 1;
+    }
+
+    let out = Path::new(&cfg.out).join(format!("{}.md", DEPRECATED_PAGE));
+    let deprecated = deprecated_md(&files, |s| {
+        format!("{}#{}", relative_url(DEPRECATED_PAGE, &s.page), s.anchor)
+    });
+    if read_to_string(&out).ok().as_ref() != Some(&deprecated) {
+        write_file(&out.to_string_lossy(), &deprecated)
+            .map_err(|e| Error::failed(7, "Writing deprecated page", e))?;
+        written += 1;
+    }
+
+    let mut cache = Cache::new(key);
+    for ((s, _, _), (entry, _)) in sources.iter().zip(entries) {
+        cache.insert(s, entry);
+    }
+    println!(
+        ":> {} of {} headers rendered, {} files written.",
+        pages.iter().flatten().count(),
+        sources.len(),
+        written
+    );
+    if let Err(e) = cache.save(&cfg.out) {
+        eprintln!("::> Warning: cannot write cache.\nError: {}.", e);
+    }
+    Ok(())
+}
+
+fn write_json(cfg: &Config, srcs: Vec<String>, impls: &CImplDocs) -> Result<(), Error> {
+    let pages = parse_pages(cfg, srcs, impls)?;
+
+    let mut project =
+        DocProject::from_pages(&pages.iter().map(|x| &x.page).collect::<Vec<&Page>>());
+    let symbols = project
+        .files
+        .iter()
+        .map(|f| SymbolTable::file_symbols(f, cfg.include_private))
+        .collect::<Vec<FileSymbols>>();
+    project.resolve_links(&SymbolTable::from_symbols(&symbols));
+    let out = Path::new(&cfg.out).join("gx_md.json");
+    println!(":> Writing json documentation {}.", out.display());
+    create_dir_all(&cfg.out)
+        .and_then(|_| write(&out, project.to_json().render()))
+        .map_err(|e| Error::failed(7, "Writing json documentation", e))
+}
+
+fn check_coverage(cfg: &Config, srcs: Vec<String>, impls: &CImplDocs) -> Result<(), Error> {
+    let src = cfg.src.trim_end_matches(['/', '\\']);
+    let mut coverage = Coverage::new();
+    let files = parallel_map(&srcs, cfg.jobs, |s| match read_line(s) {
+        Ok(x) => Ok((s.clone(), str_parser(x, cfg, impls))),
+        Err(e) => Err(read_error(s, e)),
+    })
+    .into_iter()
+    .collect::<Result<Vec<_>, Error>>()?;
+    files.iter().for_each(|(s, content)| {
+        let name = s.strip_prefix(src).unwrap_or(s);
+        coverage.add(FileCoverage::new(name, content));
+    });
+
+    let problems = lint(&files, cfg.lint, cfg.include_private);
+
+    match cfg.json {
+        true => println!("{}", coverage.render_json(cfg.min_coverage)),
+        false => println!("{}", coverage.render_table()),
+    }
+
+    if let Some(x) = cfg.min_coverage {
+        if !coverage.passed(Some(x)) {
+            let msg = format!(
+                "Error: documentation coverage {:.1}% is below minimum {:.1}%.",
+                coverage.percent(),
+                x
+            );
+            return Err(Error::new(10, msg));
+        }
+    }
+
+    if cfg.lint == LintLevel::Error && problems > 0 {
+        let msg = format!("Error: lint found {} problems.", problems);
+        return Err(Error::new(11, msg));
     }
+    Ok(())
 }
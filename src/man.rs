@@ -0,0 +1,467 @@
+use std::{
+    collections::HashSet,
+    fs::metadata,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    config::Config,
+    error::Error,
+    file_writer::{write_file, PageFile},
+    ir::{DocDetail, DocFile, DocItem, DocKind, DocProject},
+    page::Page,
+};
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+// date of man page is the modification date of its header, so output
+// does not change between run on the same source.
+fn man_date(src: &str) -> String {
+    let time = metadata(src)
+        .and_then(|m| m.modified())
+        .unwrap_or(SystemTime::now());
+    let days = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() / 86400)
+        .unwrap_or(0) as i64;
+    civil_date(days)
+}
+
+// civil date from days since 1970-01-01, e.g. `January 1, 1970`.
+fn civil_date(days: i64) -> String {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{} {}, {}", MONTHS[(month - 1) as usize], day, year)
+}
+
+// text of doc comment as roff text: markdown link and code mark are removed,
+// blank line start new paragraph and line that would be read as request is escaped.
+fn roff(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(open) = rest.find('[') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        match after.find("](") {
+            Some(close) if !after[..close].contains('[') => {
+                out.push_str(&after[..close]);
+                rest = match after[close..].find(')') {
+                    Some(end) => &after[close + end + 1..],
+                    None => "",
+                };
+            }
+            _ => {
+                out.push('[');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out.replace('\\', "\\e")
+        .replace('`', "")
+        .lines()
+        .map(|l| match l.trim().is_empty() {
+            true => String::from(".Pp"),
+            false => escape_line(l.trim_start()),
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn escape_line(line: &str) -> String {
+    match line.starts_with(['.', '\'']) {
+        true => format!("\\&{}", line),
+        false => String::from(line),
+    }
+}
+
+// code of literal display, kept as written.
+fn literal(code: &str) -> String {
+    code.replace('\\', "\\e")
+        .lines()
+        .map(escape_line)
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// argument of macro, quoted when it has space.
+fn arg(str: &str) -> String {
+    match str.contains(' ') {
+        true => format!("\"{}\"", str.replace('"', "\\(dq")),
+        false => String::from(str),
+    }
+}
+
+fn header_name(file: &DocFile) -> String {
+    file.path
+        .replace('\\', "/")
+        .trim_start_matches('/')
+        .to_string()
+}
+
+// `.Ft` and `.Fn` lines of function prototype.
+fn synopsis(item: &DocItem) -> Vec<String> {
+    let (returns, params) = match &item.detail {
+        DocDetail::Function { returns, params } => (returns, params),
+        _ => return vec![],
+    };
+    let params = params
+        .iter()
+        .map(|p| match p.name.as_ref() {
+            // pointer mark stay on name like in declaration
+            Some(n) => {
+                let unit = p.ty.trim_end_matches('*');
+                arg(&format!("{} {}{}", unit, &p.ty[unit.len()..], n))
+            }
+            None => arg(&p.ty),
+        })
+        .collect::<Vec<String>>();
+    vec![
+        format!(".Ft {}", arg(returns)),
+        format!(".Fn {} {}", item.name, params.join(" "))
+            .trim_end()
+            .to_string(),
+    ]
+}
+
+fn see_also(names: &[String]) -> Vec<String> {
+    if names.is_empty() {
+        return vec![];
+    }
+    let mut lines = vec![String::from(".Sh SEE ALSO")];
+    let last = names.len() - 1;
+    names.iter().enumerate().for_each(|(i, n)| match i == last {
+        true => lines.push(format!(".Xr {} 3", n)),
+        false => lines.push(format!(".Xr {} 3 ,", n)),
+    });
+    lines
+}
+
+fn function_page(file: &DocFile, item: &DocItem, date: &str) -> String {
+    let header = header_name(file);
+    let summary = item.summary.clone().unwrap_or_default();
+    let mut lines = vec![
+        format!(".Dd {}", date),
+        format!(".Dt {} 3", item.name.to_uppercase()),
+        String::from(".Os"),
+        String::from(".Sh NAME"),
+        format!(".Nm {}", item.name),
+        format!(
+            ".Nd {}",
+            roff(summary.split(". ").next().unwrap_or_default())
+        ),
+        String::from(".Sh SYNOPSIS"),
+        format!(".In {}", header),
+    ];
+    lines.extend(synopsis(item));
+
+    lines.push(String::from(".Sh DESCRIPTION"));
+    if let Some(x) = item.deprecated.as_ref() {
+        lines.push(String::from(".Sy Deprecated ."));
+        if !x.is_empty() {
+            lines.push(roff(x));
+        }
+        lines.push(String::from(".Pp"));
+    }
+    if !summary.is_empty() {
+        lines.push(roff(&summary));
+    }
+    // parameters of declaration like in markdown table, `@param` of unknown
+    // name is left out
+    let params = item
+        .rows
+        .params
+        .iter()
+        .filter(|p| !p.name.is_empty())
+        .collect::<Vec<_>>();
+    if !params.is_empty() {
+        lines.push(String::from(".Bl -tag -width Ds"));
+        for p in params {
+            lines.push(format!(".It Fa {}", p.name));
+            if !p.desc.is_empty() {
+                lines.push(roff(&p.desc));
+            }
+        }
+        lines.push(String::from(".El"));
+    }
+
+    let returns = item
+        .tags
+        .iter()
+        .filter(|(t, _)| t == "return" || t == "returns")
+        .map(|(_, x)| roff(x))
+        .collect::<Vec<String>>();
+    if !returns.is_empty() {
+        lines.push(String::from(".Sh RETURN VALUE"));
+        lines.push(returns.join("\n.Pp\n"));
+    }
+
+    let mut see = item
+        .tags
+        .iter()
+        .filter(|(t, _)| t == "see")
+        .filter_map(|(_, x)| x.split_whitespace().next())
+        .map(|x| x.trim_matches('`').trim_end_matches("()").to_string())
+        .collect::<Vec<String>>();
+    see.push(file.title.clone());
+    lines.extend(see_also(&see));
+    lines.join("\n") + "\n"
+}
+
+// names of `@param` that are not parameter of function.
+fn unknown_params(item: &DocItem) -> Vec<&str> {
+    item.tags
+        .iter()
+        .filter(|(t, _)| t == "param")
+        .filter_map(|(_, x)| x.split_whitespace().next())
+        .filter(|x| !item.rows.params.iter().any(|p| p.name == *x))
+        .collect()
+}
+
+fn header_page(file: &DocFile, items: &[&DocItem], date: &str) -> String {
+    let header = header_name(file);
+    let main = file.main.clone().unwrap_or_default();
+    let summary = main.lines().next().unwrap_or_default();
+    let mut lines = vec![
+        format!(".Dd {}", date),
+        format!(".Dt {} 3", file.title.to_uppercase()),
+        String::from(".Os"),
+        String::from(".Sh NAME"),
+        format!(".Nm {}", file.title),
+        format!(".Nd {}", roff(summary)),
+        String::from(".Sh SYNOPSIS"),
+        format!(".In {}", header),
+    ];
+    for item in items.iter().filter(|i| i.kind == DocKind::Function) {
+        lines.extend(synopsis(item));
+    }
+
+    lines.push(String::from(".Sh DESCRIPTION"));
+    if !main.is_empty() {
+        lines.push(roff(&main));
+    }
+    let objects = items
+        .iter()
+        .filter(|i| i.kind == DocKind::Object)
+        .collect::<Vec<_>>();
+    if !objects.is_empty() {
+        lines.push(String::from(".Ss Types"));
+        for item in objects {
+            lines.push(String::from(".Bd -literal -offset indent"));
+            lines.push(literal(&item.code));
+            lines.push(String::from(".Ed"));
+            if let Some(x) = item.summary.as_ref() {
+                lines.push(roff(x));
+            }
+        }
+    }
+
+    let functions = items
+        .iter()
+        .filter(|i| i.kind == DocKind::Function && i.desc.is_some())
+        .map(|i| i.name.clone())
+        .collect::<Vec<String>>();
+    lines.extend(see_also(&functions));
+    lines.join("\n") + "\n"
+}
+
+// write man3/ with page of every documented function and of every header.
+pub(crate) fn write_man(cfg: &Config, pages: Vec<PageFile>) -> Result<(), Error> {
+    let project = DocProject::from_pages(&pages.iter().map(|x| &x.page).collect::<Vec<&Page>>());
+    let dir = Path::new(&cfg.out).join("man3");
+    let src = cfg.src.trim_end_matches(['/', '\\']);
+
+    let mut written = HashSet::new();
+    let mut out = vec![];
+    for file in &project.files {
+        let date = man_date(&format!("{}{}", src, file.path));
+        let items = file
+            .items
+            .iter()
+            .filter(|i| !i.private || cfg.include_private)
+            .collect::<Vec<&DocItem>>();
+        out.push((file.title.clone(), header_page(file, &items, &date)));
+        for item in items
+            .iter()
+            .filter(|i| i.kind == DocKind::Function && i.desc.is_some())
+        {
+            // the first definition win like in symbol table
+            if !written.insert(item.name.clone()) {
+                eprintln!(
+                    "::> Warning: man page of {} already written, {} is skipped.",
+                    item.name, file.path
+                );
+                continue;
+            }
+            for name in unknown_params(item) {
+                eprintln!(
+                    "{}:{}: warning: `@param {}` is not a parameter of {}.",
+                    file.path, item.line, name, item.name
+                );
+            }
+            out.push((item.name.clone(), function_page(file, item, &date)));
+        }
+    }
+
+    println!(":> Writing {} man pages in {}.", out.len(), dir.display());
+    for (name, page) in out {
+        let path = dir.join(format!("{}.3", name));
+        write_file(&path.to_string_lossy(), &page)
+            .map_err(|e| Error::failed(7, &format!("Writing man page {}", name), e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufRead;
+
+    use super::*;
+    use crate::{parser::str_parser, utils::c_impl::CImplDocs};
+
+    fn project(text: &str) -> DocProject {
+        let args = ["gx_md", "-src", "src", "-o", "out", "-h", "t.h"];
+        let cfg = Config::from_args(args.iter().map(|x| x.to_string()).collect()).unwrap();
+        let mut page = Page::new();
+        page.set_content(Some(str_parser(
+            text.as_bytes().lines(),
+            &cfg,
+            &CImplDocs::new(),
+        )));
+        page.set_path_src("/sub/buf.h");
+        page.set_url("Buf");
+        page.set_title("buf.h");
+        DocProject::from_pages(&[&page])
+    }
+
+    #[test]
+    fn roff_escape() {
+        assert_eq!(roff(".start \\n 'quote"), "\\&.start \\en 'quote");
+        assert_eq!(roff("a\n  .b\n'c"), "a\n\\&.b\n\\&'c");
+        assert_eq!(roff("see [`open`](#open) now"), "see open now");
+        assert_eq!(roff("[x] and [y](u)"), "[x] and y");
+        assert_eq!(roff("one\n\ntwo"), "one\n.Pp\ntwo");
+        assert_eq!(literal(".x = 1;\n\\n"), "\\&.x = 1;\n\\en");
+        assert_eq!(arg("struct Obj *"), "\"struct Obj *\"");
+        assert_eq!(arg("say \"hi\""), "\"say \\(dqhi\\(dq\"");
+    }
+
+    #[test]
+    fn date_of_days() {
+        assert_eq!(civil_date(0), "January 1, 1970");
+        assert_eq!(civil_date(59), "March 1, 1970");
+        assert_eq!(civil_date(11016), "February 29, 2000");
+        assert_eq!(civil_date(19722), "December 31, 2023");
+        assert_eq!(civil_date(-1), "December 31, 1969");
+    }
+
+    #[test]
+    fn function_layout() {
+        let project = project(concat!(
+            "//! close buffer. it is freed\n",
+            "//! @param id handle\n",
+            "//! @param ident not there\n",
+            "//! @return 0 on success\n",
+            "//! @see buf_open()\n",
+            "int buf_close(int id, char *name);\n",
+        ));
+        let file = &project.files[0];
+        let item = &file.items[0];
+        assert_eq!(unknown_params(item), vec!["ident"]);
+        assert_eq!(
+            function_page(file, item, "May 1, 2024"),
+            concat!(
+                ".Dd May 1, 2024\n",
+                ".Dt BUF_CLOSE 3\n",
+                ".Os\n",
+                ".Sh NAME\n",
+                ".Nm buf_close\n",
+                ".Nd close buffer\n",
+                ".Sh SYNOPSIS\n",
+                ".In sub/buf.h\n",
+                ".Ft int\n",
+                ".Fn buf_close \"int id\" \"char *name\"\n",
+                ".Sh DESCRIPTION\n",
+                "close buffer. it is freed\n",
+                ".Bl -tag -width Ds\n",
+                ".It Fa id\n",
+                "handle\n",
+                ".It Fa name\n",
+                ".El\n",
+                ".Sh RETURN VALUE\n",
+                "0 on success\n",
+                ".Sh SEE ALSO\n",
+                ".Xr buf_open 3 ,\n",
+                ".Xr buf.h 3\n",
+            )
+        );
+    }
+
+    #[test]
+    fn header_layout() {
+        let project = project(concat!(
+            "///! Buffers.\n",
+            "\n",
+            "//! a buffer\n",
+            "struct Buf {\n",
+            "    int id;\n",
+            "};\n",
+            "\n",
+            "//! open it\n",
+            "int buf_open(void);\n",
+            "\n",
+            "int buf_size(void);\n",
+        ));
+        let file = &project.files[0];
+        let items = file.items.iter().collect::<Vec<&DocItem>>();
+        assert_eq!(
+            header_page(file, &items, "May 1, 2024"),
+            concat!(
+                ".Dd May 1, 2024\n",
+                ".Dt BUF.H 3\n",
+                ".Os\n",
+                ".Sh NAME\n",
+                ".Nm buf.h\n",
+                ".Nd Buffers.\n",
+                ".Sh SYNOPSIS\n",
+                ".In sub/buf.h\n",
+                ".Ft int\n",
+                ".Fn buf_open void\n",
+                ".Ft int\n",
+                ".Fn buf_size void\n",
+                ".Sh DESCRIPTION\n",
+                "Buffers.\n",
+                ".Ss Types\n",
+                ".Bd -literal -offset indent\n",
+                "struct Buf{\n",
+                "\tint id;\n",
+                "};\n",
+                ".Ed\n",
+                "a buffer\n",
+                ".Sh SEE ALSO\n",
+                ".Xr buf_open 3\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,278 @@
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use crate::{
+    config::Config,
+    deprecated::deprecated_md,
+    error::Error,
+    file_writer::{tree_key, write_file, PageFile},
+    index::index_md,
+    ir::DocProject,
+    page::Page,
+    symbols::{relative_url, Linker, SymbolTable},
+    template::Templates,
+    worker::parallel_map,
+};
+
+// chapter listing every symbol of the book.
+const INDEX_BOOK: &str = "symbol-index.md";
+const DEPRECATED_BOOK: &str = "deprecated.md";
+
+// path of page in book `src/` directory, the same tree as headers.
+// home header become README.md that mdBook use as index of the book.
+fn book_path(page: &Page) -> String {
+    if page.get_url() == "Home" {
+        return String::from("README.md");
+    }
+    let path = page.get_path_src().replace('\\', "/");
+    let path = path.trim_start_matches('/');
+    match path.strip_suffix(".h") {
+        Some(x) => format!("{}.md", x),
+        None => format!("{}.md", path),
+    }
+}
+
+// write mdBook project: book.toml, src/SUMMARY.md and one chapter per header.
+pub(crate) fn write_mdbook(
+    cfg: &Config,
+    pages: Vec<PageFile>,
+    templates: Arc<Templates>,
+) -> Result<(), Error> {
+    let paths = pages
+        .iter()
+        .map(|x| book_path(&x.page))
+        .collect::<Vec<String>>();
+    // wiki url used by include to path in book
+    let urls = pages
+        .iter()
+        .zip(&paths)
+        .map(|(x, p)| (x.page.get_url(), p.clone()))
+        .collect::<HashMap<String, String>>();
+
+    let mut project =
+        DocProject::from_pages(&pages.iter().map(|x| &x.page).collect::<Vec<&Page>>());
+    project
+        .files
+        .iter_mut()
+        .zip(&paths)
+        .for_each(|(f, p)| f.url = p.clone());
+
+    println!(":> Building symbol table.");
+    let symbols = project
+        .files
+        .iter()
+        .map(|f| SymbolTable::file_symbols(f, cfg.include_private))
+        .collect::<Vec<_>>();
+    let table = Arc::new(SymbolTable::from_symbols(&symbols));
+
+    let mut pages = pages;
+    pages.iter_mut().zip(&paths).for_each(|(pf, path)| {
+        let linker = Linker::new(table.clone(), path, &pf.page.get_path_src());
+        pf.page.set_linker(Some(linker));
+        pf.page.set_templates(templates.clone());
+        pf.page
+            .map_include_url(|url| urls.get(url).map(|x| relative_url(path, x)));
+        // mdBook has no page sidebar, symbols are listed at top of the chapter
+        pf.page.set_toc(true);
+    });
+
+    let src = Path::new(&cfg.out).join("src");
+    let items = pages.iter().zip(&paths).collect::<Vec<_>>();
+    parallel_map(&items, cfg.jobs, |(pf, path)| {
+        let name = pf.page.get_path_src();
+        println!(":> Writing documentation of file: {}.", &name);
+        let out = src.join(path);
+        match write_file(&out.to_string_lossy(), &pf.page.render_content()) {
+            Ok(_) => {
+                println!("::> Documentation for file {} : Success.", &name);
+                Ok(())
+            }
+            Err(e) => Err(Error::failed(
+                7,
+                &format!("Documentation file: {}", &name),
+                e,
+            )),
+        }
+    })
+    .into_iter()
+    .collect::<Result<(), Error>>()?;
+
+    let files = symbols.iter().collect::<Vec<_>>();
+    let index = index_md(&files, |s| {
+        format!("{}#{}", relative_url(INDEX_BOOK, &s.page), s.anchor)
+    });
+    write_file(&src.join(INDEX_BOOK).to_string_lossy(), &index)
+        .map_err(|e| Error::failed(7, "Writing symbol index", e))?;
+
+    let deprecated = deprecated_md(&files, |s| {
+        format!("{}#{}", relative_url(DEPRECATED_BOOK, &s.page), s.anchor)
+    });
+    write_file(&src.join(DEPRECATED_BOOK).to_string_lossy(), &deprecated)
+        .map_err(|e| Error::failed(7, "Writing deprecated page", e))?;
+
+    let summary = summary(&pages, &paths);
+    write_file(&src.join("SUMMARY.md").to_string_lossy(), &summary)
+        .map_err(|e| Error::failed(7, "Writing SUMMARY.md", e))?;
+
+    // book.toml belong to user once created
+    let toml = Path::new(&cfg.out).join("book.toml");
+    if !toml.exists() {
+        let title = pages
+            .iter()
+            .find(|x| x.page.get_url() == "Home")
+            .map(|x| x.page.get_title())
+            .unwrap_or_default();
+        let content = format!(
+            "[book]\ntitle = \"{}\"\nsrc = \"src\"\n\n[output.html]\n",
+            title.replace('"', "\\\"")
+        );
+        write_file(&toml.to_string_lossy(), &content)
+            .map_err(|e| Error::failed(7, "Writing book.toml", e))?;
+    }
+    Ok(())
+}
+
+// table of content of the book, home header first then header tree.
+// directory is a draft chapter holding its headers.
+fn summary(pages: &[PageFile], paths: &[String]) -> String {
+    let mut lines = vec![String::from("# Summary"), String::new()];
+    let mut chapters = vec![];
+    for (pf, path) in pages.iter().zip(paths) {
+        match path.as_str() {
+            "README.md" => {
+                lines.push(format!("[{}](README.md)", pf.page.get_title()));
+                lines.push(String::new());
+            }
+            _ => chapters.push((path.as_str(), pf.page.get_title())),
+        }
+    }
+    // files of a directory come before its sub directories
+    chapters.sort_by_key(|(p, _)| tree_key(p));
+
+    let mut open: Vec<&str> = vec![];
+    for (path, title) in chapters {
+        let dirs = path.split('/').collect::<Vec<&str>>();
+        let dirs = &dirs[..dirs.len() - 1];
+        let common = open.iter().zip(dirs).take_while(|(a, b)| a == b).count();
+        open.truncate(common);
+        for d in &dirs[common..] {
+            lines.push(format!("{}- [{}/]()", "  ".repeat(open.len()), d));
+            open.push(d);
+        }
+        lines.push(format!(
+            "{}- [{}]({})",
+            "  ".repeat(open.len()),
+            title,
+            path
+        ));
+    }
+    // symbol index and deprecated page are suffix chapters, after the header tree
+    lines.push(String::new());
+    lines.push(format!("[Index]({})", INDEX_BOOK));
+    lines.push(format!("[Deprecated]({})", DEPRECATED_BOOK));
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env::temp_dir, fs, io::BufRead};
+
+    use super::*;
+    use crate::{parser::str_parser, utils::c_impl::CImplDocs};
+
+    fn page(cfg: &Config, path: &str, url: &str, text: &str) -> PageFile {
+        let mut page = Page::new();
+        page.set_content(Some(str_parser(
+            text.as_bytes().lines(),
+            cfg,
+            &CImplDocs::new(),
+        )));
+        page.set_path_src(path);
+        page.set_url(url);
+        page.set_title(path.rsplit('/').next().unwrap_or_default());
+        PageFile {
+            page,
+            out_page: String::new(),
+            out_side: String::new(),
+        }
+    }
+
+    fn config(out: &str) -> Config {
+        let args = ["gx_md", "-src", "src", "-o", out, "-h", "home.h"];
+        Config::from_args(args.iter().map(|x| x.to_string()).collect()).unwrap()
+    }
+
+    #[test]
+    fn summary_tree() {
+        let cfg = config("out");
+        let pages = [
+            ("/sub/deep/d.h", "D"),
+            ("/util.h", "Util"),
+            ("/sub/t.h", "T"),
+            ("/home.h", "Home"),
+            ("/other/o.h", "O"),
+            ("/sub/a.h", "A"),
+        ]
+        .iter()
+        .map(|(path, url)| page(&cfg, path, url, "int x(void);\n"))
+        .collect::<Vec<PageFile>>();
+        let paths = pages
+            .iter()
+            .map(|x| book_path(&x.page))
+            .collect::<Vec<String>>();
+        assert_eq!(paths[0], "sub/deep/d.md");
+        assert_eq!(paths[3], "README.md");
+        assert_eq!(
+            summary(&pages, &paths),
+            concat!(
+                "# Summary\n",
+                "\n",
+                "[home.h](README.md)\n",
+                "\n",
+                "- [util.h](util.md)\n",
+                "- [other/]()\n",
+                "  - [o.h](other/o.md)\n",
+                "- [sub/]()\n",
+                "  - [a.h](sub/a.md)\n",
+                "  - [t.h](sub/t.md)\n",
+                "  - [deep/]()\n",
+                "    - [d.h](sub/deep/d.md)\n",
+                "\n",
+                "[Index](symbol-index.md)\n",
+                "[Deprecated](deprecated.md)\n",
+            )
+        );
+    }
+
+    #[test]
+    fn relative_links() {
+        let dir = temp_dir().join(format!("gx_md_mdbook_{}", std::process::id()));
+        let cfg = config(&dir.to_string_lossy());
+        let pages = vec![
+            page(
+                &cfg,
+                "/home.h",
+                "Home",
+                "#include \"t.h\"\n\n//! see [`gx_open`]\nvoid gx_home(void);\n",
+            ),
+            page(
+                &cfg,
+                "/sub/t.h",
+                "T",
+                "#include \"home.h\"\n\n//! open it\nint gx_open(void);\n",
+            ),
+        ];
+        assert!(write_mdbook(&cfg, pages, Templates::shared()).is_ok());
+        let read = |x: &str| fs::read_to_string(dir.join(x)).unwrap();
+        let home = read("src/README.md");
+        let t = read("src/sub/t.md");
+        let index = read("src/symbol-index.md");
+        let toml = read("book.toml");
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(home.contains("* [t.h](sub/t.md)"), "{}", home);
+        assert!(home.contains("[`gx_open`](sub/t.md#gx_open)"), "{}", home);
+        assert!(t.contains("* [home.h](../README.md)"), "{}", t);
+        assert!(index.contains("[`gx_open`](sub/t.md#gx_open)"), "{}", index);
+        assert!(toml.starts_with("[book]\ntitle = \"home.h\"\n"), "{}", toml);
+    }
+}
@@ -1,132 +1,404 @@
-use std::cell::RefCell;
+use std::sync::Arc;
 
 use crate::{
+    config::Fields,
+    diagram::class_diagram,
+    json::Json,
     page::{
-        view::{FieldView, IntoViewAnchor},
+        view::{FieldView, IntoViewAnchor, ViewContext},
         Renderer,
     },
-    utils::{c_function::CFunction, c_includes::CIncludes, c_object::CObject, CommentMain, IntoMd},
+    slug::{headings, Slugger},
+    symbols::Linker,
+    template::Templates,
+    utils::{
+        c_function::CFunction, c_includes::CIncludes, c_object::CObject, AnchorMd, CommentMain,
+        IntoMd, TitleMd,
+    },
 };
 
-use super::side_panel::SidePanel;
+use super::{group::Group, side_panel::SidePanel, view::link::Link};
 
 #[derive(Clone)]
 pub(crate) struct Content {
-    main: RefCell<Option<CommentMain>>,
-    object: RefCell<Vec<FieldView<CObject>>>,
-    func: RefCell<Vec<FieldView<CFunction>>>,
-    incl: RefCell<Vec<FieldView<CIncludes>>>,
+    main: Option<CommentMain>,
+    object: Vec<FieldView<CObject>>,
+    func: Vec<FieldView<CFunction>>,
+    incl: Vec<FieldView<CIncludes>>,
+    groups: Vec<Group>,
+    show_private: bool,
+    linker: Option<Linker>,
+    templates: Arc<Templates>,
+    fields: Fields,
+    diagram: bool,
 }
 
+const INTERNAL: &str = "Internal";
+
 impl Content {
     pub(crate) fn new() -> Self {
         Content {
-            main: RefCell::new(None),
-            object: RefCell::new(vec![]),
-            func: RefCell::new(vec![]),
-            incl: RefCell::new(vec![]),
+            main: None,
+            object: vec![],
+            func: vec![],
+            incl: vec![],
+            groups: vec![],
+            show_private: false,
+            linker: None,
+            templates: Templates::shared(),
+            fields: Fields::Both,
+            diagram: false,
+        }
+    }
+
+    pub(crate) fn set_linker(&mut self, linker: Option<Linker>) {
+        self.linker = linker;
+    }
+
+    pub(crate) fn set_templates(&mut self, templates: Arc<Templates>) {
+        self.templates = templates;
+    }
+
+    pub(crate) fn set_fields(&mut self, fields: Fields) {
+        self.fields = fields;
+    }
+
+    // show class diagram of structs of the page.
+    pub(crate) fn set_diagram(&mut self, diagram: bool) {
+        self.diagram = diagram;
+    }
+
+    pub(crate) fn set_show_private(&mut self, show: bool) {
+        self.show_private = show;
+    }
+
+    pub(crate) fn set_main(&mut self, main: Option<CommentMain>) {
+        self.main = main;
+    }
+
+    pub(crate) fn get_main(&self) -> Option<CommentMain> {
+        let a = &self.main;
+        a.clone()
+    }
+
+    pub(crate) fn add_object(&mut self, obj: FieldView<CObject>) {
+        self.object.push(obj);
+    }
+
+    pub(crate) fn get_objects(&self) -> Vec<FieldView<CObject>> {
+        let a = &self.object;
+        a.clone()
+    }
+
+    // objects rendered on the page, private ones only when shown.
+    pub(crate) fn visible_objects(&self) -> Vec<CObject> {
+        self.object
+            .iter()
+            .filter(|x| self.show_private || !x.is_private())
+            .map(|x| x.get_field())
+            .collect()
+    }
+
+    // structs of the page, structs of other pages used by their fields are linked
+    // when linker know them.
+    pub(crate) fn class_diagram(&self) -> Option<String> {
+        if !self.diagram {
+            return None;
         }
+        let linker = self.linker.as_ref();
+        class_diagram(&self.visible_objects(), |n| {
+            linker.and_then(|l| l.object_title(n))
+        })
     }
 
-    pub(crate) fn set_main(&self, main: Option<CommentMain>) {
-        *self.main.borrow_mut() = main;
+    pub(crate) fn add_func(&mut self, fun: FieldView<CFunction>) {
+        self.func.push(fun);
     }
 
-    // pub(crate) fn get_main(&self) -> Option<CommentMain> {
-    //     let a = self.main.borrow();
-    //     a.clone()
-    // }
+    pub(crate) fn get_func(&self) -> Vec<FieldView<CFunction>> {
+        let a = &self.func;
+        a.clone()
+    }
 
-    pub(crate) fn add_object(&self, obj: FieldView<CObject>) {
-        self.object.borrow_mut().push(obj);
+    pub(crate) fn add_include(&mut self, inc: FieldView<CIncludes>) {
+        self.incl.push(inc);
     }
 
-    // pub(crate) fn get_objects(&self) -> Vec<FieldView<CStruct>> {
-    //     let a = self.object.borrow();
-    //     a.clone()
-    // }
+    pub(crate) fn get_include(&self) -> Vec<FieldView<CIncludes>> {
+        let a = &self.incl;
+        a.clone()
+    }
 
-    pub(crate) fn add_func(&self, fun: FieldView<CFunction>) {
-        self.func.borrow_mut().push(fun);
+    pub(crate) fn map_include_url<F>(&mut self, f: F)
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        self.incl.iter_mut().for_each(|x| {
+            let mut inc = x.get_field();
+            inc.set_url(&f(&inc.get_url()).unwrap_or_default());
+            x.set_field(inc);
+        });
     }
 
-    // pub(crate) fn get_func(&self) -> Vec<FieldView<CFunction>> {
-    //     let a = self.func.borrow();
-    //     a.clone()
-    // }
+    pub(crate) fn add_group(&mut self, group: Group) {
+        self.groups.push(group);
+    }
 
-    pub(crate) fn add_include(&self, inc: FieldView<CIncludes>) {
-        self.incl.borrow_mut().push(inc);
+    pub(crate) fn get_groups(&self) -> Vec<Group> {
+        self.groups.clone()
     }
 
-    // pub(crate) fn get_include(&self) -> Vec<CIncludes> {
-    //     let a = self.incl.borrow();
-    //     a.clone()
-    // }
+    pub(crate) fn append_group_intro(&mut self, str: &str) {
+        if let Some(x) = self.groups.last_mut() {
+            x.append_intro(str);
+        }
+    }
 
     pub(crate) fn create_side_panel(&self) -> SidePanel {
-        let sp = SidePanel::new();
-        let obj = self.object.borrow();
+        let mut sp = SidePanel::new();
+        sp.set_templates(self.templates.clone());
+        self.groups.iter().for_each(|g| sp.add_group(g.get_name()));
+
+        let show_private = self.show_private;
+        if show_private {
+            sp.add_group(INTERNAL);
+        }
+
+        let obj = &self.object;
         obj.iter().for_each(|o| {
+            if o.is_private() && !show_private {
+                return;
+            }
             if let Some(x) = o.create_anchor() {
-                sp.add_obj(x);
+                let mut link = self.side_link(x);
+                link.set_deprecated(o.get_deprecated().is_some());
+                sp.add_obj(link, side_group(o));
             }
         });
 
-        let fnc = self.func.borrow();
+        let fnc = &self.func;
         fnc.iter().for_each(|f| {
+            if f.is_private() && !show_private {
+                return;
+            }
             if let Some(x) = f.create_anchor() {
-                sp.add_fun(x);
+                let mut link = self.side_link(x);
+                link.set_deprecated(f.get_deprecated().is_some());
+                sp.add_fun(link, side_group(f));
             }
         });
 
-        let incl = self.incl.borrow();
+        let incl = &self.incl;
         incl.iter().for_each(|f| {
+            if f.is_private() && !show_private {
+                return;
+            }
             if let Some(x) = f.create_anchor() {
-                sp.add_includes(x);
+                sp.add_includes(x, side_group(f));
             }
         });
-        return sp;
+        sp
+    }
+
+    // anchor of item as written by its view.
+    fn side_link(&self, link: Link) -> Link {
+        match self.linker.as_ref().and_then(|l| l.anchor(link.get_url())) {
+            Some(x) => Link::new(link.get_name(), &x, false),
+            None => link,
+        }
     }
 }
 
-impl Renderer for Content {
-    fn render(&self) -> String {
-        let main = self.main.borrow();
-        let main = match main.as_ref() {
-            Some(x) => x.into_md(),
-            None => String::new(),
-        };
+fn side_group<T>(item: &FieldView<T>) -> Option<String>
+where
+    T: IntoMd + TitleMd + AnchorMd + Clone,
+{
+    match item.is_private() {
+        true => Some(String::from(INTERNAL)),
+        false => item.get_group(),
+    }
+}
+
+// item of content by its position in list of its kind.
+#[derive(Clone, Copy)]
+enum Slot {
+    Object(usize),
+    Function(usize),
+    Include(usize),
+}
+
+// section of rendered content: heading, kind of section, intro and items in order.
+struct Section<'a> {
+    title: &'a str,
+    kind: &'a str,
+    intro: Option<String>,
+    slots: Vec<Slot>,
+}
+
+impl Section<'_> {
+    fn heading(&self) -> String {
+        match self.kind {
+            "group" => String::from(self.title),
+            _ => format!("{}:", self.title),
+        }
+    }
+}
 
-        let obj = self.object.borrow();
-        let obj = if obj.len() > 0 {
-            let s: Vec<String> = obj.iter().map(|o| o.into_view()).collect::<Vec<String>>();
-            let s = s.join("\n");
-            format!("\n### **Objects:**\n\n---\n\n{}", s)
-        } else {
-            String::new()
+fn slots<T, F>(items: &[FieldView<T>], slot: F, keep: &dyn Fn(&FieldView<T>) -> bool) -> Vec<Slot>
+where
+    T: IntoMd + TitleMd + AnchorMd + Clone,
+    F: Fn(usize) -> Slot,
+{
+    items
+        .iter()
+        .enumerate()
+        .filter(|(_, x)| keep(x))
+        .map(|(i, _)| slot(i))
+        .collect()
+}
+
+impl Content {
+    // sections in rendered order, ungrouped section is left out when empty, group
+    // is always there.
+    fn sections(&self) -> Vec<Section<'_>> {
+        let in_group = |group: Option<&str>| {
+            let obj = slots(&self.object, Slot::Object, &|x| {
+                !x.is_private() && x.get_group().as_deref() == group
+            });
+            let fun = slots(&self.func, Slot::Function, &|x| {
+                !x.is_private() && x.get_group().as_deref() == group
+            });
+            let inc = slots(&self.incl, Slot::Include, &|x| {
+                !x.is_private() && x.get_group().as_deref() == group
+            });
+            (obj, fun, inc)
         };
 
-        let fun = self.func.borrow();
+        let (obj, fun, inc) = in_group(None);
+        let mut sections = [("Objects", obj), ("Functions", fun), ("Includes", inc)]
+            .into_iter()
+            .filter(|(_, slots)| !slots.is_empty())
+            .map(|(title, slots)| Section {
+                title,
+                kind: "section",
+                intro: None,
+                slots,
+            })
+            .collect::<Vec<Section>>();
+
+        self.groups.iter().for_each(|g| {
+            let (obj, fun, inc) = in_group(Some(g.get_name()));
+            sections.push(Section {
+                title: g.get_name(),
+                kind: "group",
+                intro: g.get_intro(),
+                slots: [obj, fun, inc].concat(),
+            });
+        });
+
+        if self.show_private {
+            let private = [
+                slots(&self.object, Slot::Object, &|x| x.is_private()),
+                slots(&self.func, Slot::Function, &|x| x.is_private()),
+                slots(&self.incl, Slot::Include, &|x| x.is_private()),
+            ]
+            .concat();
+            if !private.is_empty() {
+                sections.push(Section {
+                    title: INTERNAL,
+                    kind: "internal",
+                    intro: None,
+                    slots: private,
+                });
+            }
+        }
+        sections
+    }
 
-        let fun = if fun.len() > 0 {
-            let s: Vec<String> = fun.iter().map(|o| o.into_view()).collect::<Vec<String>>();
-            let s = s.join("\n");
-            format!("\n### **Functions:**\n\n---\n\n{}", s)
-        } else {
-            String::new()
+    fn view(&self, slot: Slot) -> String {
+        let cx = ViewContext {
+            linker: self.linker.as_ref(),
+            templates: self.templates.as_ref(),
+            fields: self.fields,
         };
+        match slot {
+            Slot::Object(i) => self.object[i].into_view(&cx),
+            Slot::Function(i) => self.func[i].into_view(&cx),
+            Slot::Include(i) => self.incl[i].into_view(&cx),
+        }
+    }
 
-        let inc = self.incl.borrow();
+    // give every item heading the id github give it, counting every heading of
+    // the page in the order of default layout so duplicate title get `-1`, `-2`.
+    pub(crate) fn assign_anchors(&mut self, title: &str, toc: bool) {
+        let mut slugger = Slugger::new();
+        slugger.slug(title);
+        if let Some(x) = self.main.as_ref() {
+            headings(&x.into_md()).iter().for_each(|h| {
+                slugger.slug(h);
+            });
+        }
+        if toc {
+            self.create_side_panel().headings().iter().for_each(|h| {
+                slugger.slug(h);
+            });
+        }
+
+        let sections = self
+            .sections()
+            .into_iter()
+            .map(|s| (s.heading(), s.slots))
+            .collect::<Vec<_>>();
+        for (heading, slots) in sections {
+            slugger.slug(&heading);
+            for slot in slots {
+                match slot {
+                    Slot::Object(i) => {
+                        let x = &mut self.object[i];
+                        x.set_anchor(&slugger.slug(&x.get_field().create_title()));
+                    }
+                    Slot::Function(i) => {
+                        let x = &mut self.func[i];
+                        x.set_anchor(&slugger.slug(&x.get_field().create_title()));
+                    }
+                    // include is a list line, not a heading
+                    Slot::Include(_) => {}
+                }
+            }
+        }
+    }
 
-        let inc = if inc.len() > 0 {
-            let s: Vec<String> = inc.iter().map(|o| o.into_view()).collect::<Vec<String>>();
-            let s = s.join("\n");
-            format!("\n### **Includes:**\n\n---\n\n{}", s)
-        } else {
-            String::new()
+    // values of content template: main comment and its sections.
+    pub(crate) fn context(&self) -> Json {
+        let main = match self.main.as_ref() {
+            Some(x) => x.into_md(),
+            None => String::new(),
         };
 
-        format!("{}{}{}{}", main, obj, fun, inc)
+        let sections = self
+            .sections()
+            .into_iter()
+            .map(|s| {
+                let views = s.slots.iter().map(|x| Json::Str(self.view(*x)));
+                Json::obj(vec![
+                    ("title", Json::str(s.title)),
+                    ("group", Json::Bool(s.kind == "group")),
+                    ("internal", Json::Bool(s.kind == "internal")),
+                    ("intro", Json::Str(s.intro.unwrap_or_default())),
+                    ("items", Json::Arr(views.collect())),
+                ])
+            })
+            .collect::<Vec<Json>>();
+
+        Json::obj(vec![
+            ("main", Json::Str(main)),
+            ("sections", Json::Arr(sections)),
+        ])
+    }
+}
+
+impl Renderer for Content {
+    fn render(&self) -> String {
+        self.templates.render("content", &self.context())
     }
 }
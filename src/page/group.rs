@@ -0,0 +1,33 @@
+// named subsection of a page, started by `//! @group Name` or `///! ## Name`.
+#[derive(Clone)]
+pub(crate) struct Group {
+    name: String,
+    intro: Option<String>,
+}
+
+impl Group {
+    pub(crate) fn new(name: &str) -> Self {
+        Group {
+            name: String::from(name),
+            intro: None,
+        }
+    }
+
+    pub(crate) fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn append_intro(&mut self, str: &str) {
+        match self.intro.as_mut() {
+            Some(x) => {
+                x.push(' ');
+                x.push_str(str);
+            }
+            None => self.intro = Some(String::from(str)),
+        }
+    }
+
+    pub(crate) fn get_intro(&self) -> Option<String> {
+        self.intro.clone()
+    }
+}
@@ -1,8 +1,11 @@
-use std::cell::RefCell;
+use std::sync::Arc;
 
+use crate::{json::Json, symbols::Linker, template::Templates};
 use {content::Content, side_panel::SidePanel};
 
 pub(crate) mod content;
+pub(crate) mod group;
+pub(crate) mod navigation;
 pub(crate) mod side_panel;
 pub(crate) mod view;
 
@@ -11,52 +14,152 @@ pub(crate) trait Renderer {
 }
 
 pub(crate) struct Page {
-    title: RefCell<String>,
-    path_src: RefCell<String>,
-    md: RefCell<Option<Content>>,
-    left_side: RefCell<Option<SidePanel>>,
+    title: String,
+    path_src: String,
+    url: String,
+    md: Option<Content>,
+    left_side: Option<SidePanel>,
+    toc: bool,
+    include_graph: Option<String>,
+    class_diagram: Option<String>,
+    templates: Arc<Templates>,
 }
 
 impl Page {
     pub(crate) fn new() -> Self {
         Page {
-            title: RefCell::new(String::new()),
-            path_src: RefCell::new(String::new()),
-            md: RefCell::new(None),
-            left_side: RefCell::new(None),
+            title: String::new(),
+            path_src: String::new(),
+            url: String::new(),
+            md: None,
+            left_side: None,
+            toc: false,
+            include_graph: None,
+            class_diagram: None,
+            templates: Templates::shared(),
         }
     }
 
-    pub(crate) fn set_title(&self, str: &str) {
-        *self.title.borrow_mut() = String::from(str);
+    pub(crate) fn set_title(&mut self, str: &str) {
+        self.title = String::from(str);
+        self.update_anchors();
     }
 
-    pub(crate) fn set_content(&self, content: Option<Content>) {
-        *self.md.borrow_mut() = content;
+    pub(crate) fn set_content(&mut self, content: Option<Content>) {
+        self.md = content;
+        self.update_anchors();
     }
 
-    pub(crate) fn set_path_src(&self, path: &str) {
-        *self.path_src.borrow_mut() = String::from(path);
+    // heading ids depend on every heading of the page.
+    fn update_anchors(&mut self) {
+        if let Some(x) = self.md.as_mut() {
+            x.assign_anchors(&self.title, self.toc);
+        }
     }
 
-    pub(crate) fn render_content(&self) -> String {
-        let contn = self.md.borrow();
+    pub(crate) fn set_path_src(&mut self, path: &str) {
+        self.path_src = String::from(path);
+    }
 
-        let content = match contn.as_ref() {
-            Some(x) => format!("\n{}", x.render()),
-            None => String::new(),
+    pub(crate) fn get_title(&self) -> String {
+        self.title.to_owned()
+    }
+
+    pub(crate) fn get_path_src(&self) -> String {
+        self.path_src.to_owned()
+    }
+
+    // page name used by link to this page.
+    pub(crate) fn set_url(&mut self, url: &str) {
+        self.url = String::from(url);
+    }
+
+    pub(crate) fn get_url(&self) -> String {
+        self.url.to_owned()
+    }
+
+    // list symbols of page at its top instead of in its sidebar.
+    pub(crate) fn set_toc(&mut self, toc: bool) {
+        self.toc = toc;
+        self.update_anchors();
+    }
+
+    // mermaid diagram of headers included by this page, shown after its content.
+    pub(crate) fn set_include_graph(&mut self, graph: Option<String>) {
+        self.include_graph = graph;
+    }
+
+    pub(crate) fn get_include_graph(&self) -> Option<String> {
+        self.include_graph.clone()
+    }
+
+    // class diagram shown instead of diagram of page structs, e.g. whole project on home.
+    pub(crate) fn set_class_diagram(&mut self, diagram: Option<String>) {
+        self.class_diagram = diagram;
+    }
+
+    pub(crate) fn get_content(&self) -> Option<Content> {
+        self.md.clone()
+    }
+
+    pub(crate) fn set_linker(&mut self, linker: Option<Linker>) {
+        if let Some(x) = self.md.as_mut() {
+            x.set_linker(linker);
+        }
+    }
+
+    pub(crate) fn set_templates(&mut self, templates: Arc<Templates>) {
+        if let Some(x) = self.md.as_mut() {
+            x.set_templates(templates.clone());
+        }
+        self.templates = templates;
+    }
+
+    // url of included header page, None when header is not documented.
+    pub(crate) fn map_include_url<F>(&mut self, f: F)
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        if let Some(x) = self.md.as_mut() {
+            x.map_include_url(f);
+        }
+    }
+
+    pub(crate) fn render_content(&self) -> String {
+        let mut fields = match self.md.as_ref().map(|x| x.context()) {
+            Some(Json::Obj(x)) => x,
+            _ => vec![],
+        };
+        let sidebar = match self.toc {
+            true => self.render_side_bar().unwrap_or_default(),
+            false => String::new(),
+        };
+        let diagram = match self.class_diagram.as_ref() {
+            Some(x) => Some(x.clone()),
+            None => self.md.as_ref().and_then(|x| x.class_diagram()),
         };
-        format!(
-            "# {}\n*{}*\n\n---\n{}",
-            self.title.borrow(),
-            self.path_src.borrow(),
-            content
-        )
+        fields.extend(vec![
+            (String::from("title"), Json::str(&self.title)),
+            (String::from("path"), Json::str(&self.path_src)),
+            (String::from("url"), Json::str(&self.url)),
+            (String::from("toc"), Json::Bool(self.toc)),
+            (String::from("sidebar"), Json::Str(sidebar)),
+            (String::from("has_content"), Json::Bool(self.md.is_some())),
+            (
+                String::from("class_diagram"),
+                Json::str(&diagram.unwrap_or_default()),
+            ),
+            (
+                String::from("include_graph"),
+                Json::str(&self.include_graph.clone().unwrap_or_default()),
+            ),
+        ]);
+        self.templates.render("page", &Json::Obj(fields))
     }
 
     pub(crate) fn render_side_bar(&self) -> Option<String> {
-        let side_bar = self.left_side.borrow();
-        let contn = self.md.borrow();
+        let side_bar = &self.left_side;
+        let contn = &self.md;
 
         let side_bar = match side_bar.as_ref() {
             Some(x) => x.render(),
@@ -72,8 +175,8 @@ impl Page {
 
 impl Renderer for Page {
     fn render(&self) -> String {
-        let side_bar = self.left_side.borrow();
-        let contn = self.md.borrow();
+        let side_bar = &self.left_side;
+        let contn = &self.md;
 
         let side_bar = match side_bar.as_ref() {
             Some(x) => x.render(),
@@ -87,6 +190,6 @@ impl Renderer for Page {
             Some(x) => x.render(),
             None => String::from(""),
         };
-        format!("## {}\n{}\n{}", self.title.borrow(), side_bar, content)
+        format!("## {}\n{}\n{}", self.title, side_bar, content)
     }
 }
@@ -0,0 +1,186 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    deprecated::DEPRECATED_PAGE,
+    index::INDEX_PAGE,
+    symbols::{escape_html, FileSymbols},
+};
+
+// headers of one directory of the source tree.
+struct Dir<'a> {
+    files: Vec<(&'a str, &'a str, &'a FileSymbols)>,
+    dirs: BTreeMap<&'a str, Dir<'a>>,
+}
+
+impl<'a> Dir<'a> {
+    fn new() -> Self {
+        Dir {
+            files: vec![],
+            dirs: BTreeMap::new(),
+        }
+    }
+
+    fn render(&self, depth: usize, lines: &mut Vec<String>) {
+        let indent = "  ".repeat(depth);
+        for (name, url, symbols) in &self.files {
+            let line = match symbols.is_empty() {
+                true => format!("{}* [{}]({})", indent, name, url),
+                // html keep symbols folded, markdown has no collapsible list
+                false => format!(
+                    "{}* <details><summary><a href=\"{}\">{}</a></summary><ul>{}</ul></details>",
+                    indent,
+                    url,
+                    escape_html(name),
+                    symbols
+                        .iter()
+                        .map(|(_, s)| {
+                            let name = match s.deprecated.is_some() {
+                                true => format!("<del>{}</del>", escape_html(&s.name)),
+                                false => escape_html(&s.name),
+                            };
+                            format!("<li><a href=\"{}#{}\">{}</a></li>", url, s.anchor, name)
+                        })
+                        .collect::<Vec<String>>()
+                        .concat()
+                ),
+            };
+            lines.push(line);
+        }
+        for (name, dir) in &self.dirs {
+            lines.push(format!("{}* **{}/**", indent, name));
+            dir.render(depth + 1, lines);
+        }
+    }
+}
+
+// one sidebar for the whole wiki: directories, their headers and symbols of each header.
+// files is list of (source path, page url, symbols) like `/sub/t.h`, `T`.
+pub(crate) fn global_side_bar(files: &[(&str, &str, &FileSymbols)]) -> String {
+    let mut root = Dir::new();
+    for (path, url, symbols) in files {
+        let mut parts = path
+            .split(['/', '\\'])
+            .filter(|x| !x.is_empty())
+            .collect::<Vec<&str>>();
+        let name = match parts.pop() {
+            Some(x) => x,
+            None => continue,
+        };
+        let dir = parts
+            .into_iter()
+            .fold(&mut root, |d, p| d.dirs.entry(p).or_insert_with(Dir::new));
+        dir.files.push((name, url, symbols));
+    }
+
+    let mut lines = vec![
+        String::from("#### **[Home](Home)**"),
+        format!("#### **[Index]({})**", INDEX_PAGE),
+        format!("#### **[Deprecated]({})**", DEPRECATED_PAGE),
+    ];
+    root.render(0, &mut lines);
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env::temp_dir, fs};
+
+    use super::*;
+    use crate::{
+        config::Config,
+        file_writer::{parse_into_page, GxFile},
+        ir::DocKind,
+        symbols::Symbol,
+        utils::c_impl::CImplDocs,
+    };
+
+    fn symbol(name: &str, deprecated: bool) -> (Vec<String>, Symbol) {
+        let symbol = Symbol {
+            name: String::from(name),
+            kind: DocKind::Function,
+            page: String::new(),
+            anchor: name.to_lowercase(),
+            file: String::new(),
+            summary: String::new(),
+            uses: vec![],
+            deprecated: deprecated.then(String::new),
+        };
+        (vec![String::from(name)], symbol)
+    }
+
+    #[test]
+    fn grouped_by_directory() {
+        let home = vec![symbol("gx_init", false)];
+        let t = vec![symbol("t_open", false), symbol("t_old", true)];
+        let empty = vec![];
+        let files = [
+            ("/sub/t.h", "T", &t),
+            ("/home.h", "Home", &home),
+            ("/sub/deep/d.h", "D", &empty),
+            ("/a.h", "A", &empty),
+        ];
+        assert_eq!(
+            global_side_bar(&files),
+            [
+                "#### **[Home](Home)**",
+                "#### **[Index](Index)**",
+                "#### **[Deprecated](Deprecated)**",
+                "* <details><summary><a href=\"Home\">home.h</a></summary><ul><li><a href=\"Home#gx_init\">gx_init</a></li></ul></details>",
+                "* [a.h](A)",
+                "* **sub/**",
+                "  * <details><summary><a href=\"T\">t.h</a></summary><ul><li><a href=\"T#t_open\">t_open</a></li><li><a href=\"T#t_old\"><del>t_old</del></a></li></ul></details>",
+                "  * **deep/**",
+                "    * [d.h](D)",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn no_page_sidebar_in_global_mode() {
+        let dir = temp_dir().join(format!("gx_md_nav_{}", std::process::id()));
+        let src = dir.join("src");
+        fs::create_dir_all(src.join("sub")).unwrap();
+        let header = src.join("sub").join("t.h");
+        fs::write(&header, "//! open it\nint t_open(void);\n").unwrap();
+
+        let file = |sidebar: &str| {
+            let args = [
+                "gx_md",
+                "-src",
+                &src.to_string_lossy(),
+                "-o",
+                "out",
+                "-h",
+                "home.h",
+                "-sidebar",
+                sidebar,
+            ];
+            let cfg = Config::from_args(args.iter().map(|x| x.to_string()).collect()).unwrap();
+            let fo = GxFile {
+                dir: src.to_string_lossy().to_string(),
+                file: header.to_string_lossy().to_string(),
+                out_dir: String::from("out"),
+                home_file: String::from("home.h"),
+            };
+            match parse_into_page(&fo, &cfg, &CImplDocs::new()) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            }
+        };
+        let page = file("page");
+        let global = file("global");
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(page.out_page, "out/sub/T/T.md");
+        assert_eq!(page.out_side, "out/sub/T/_Sidebar.md");
+        assert_eq!(global.out_page, "out/sub/T/T.md");
+        assert_eq!(global.out_side, "");
+        // symbols of page move to its table of contents
+        assert!(!page.page.render_content().contains("(1)**"));
+        assert!(global
+            .page
+            .render_content()
+            .contains("#### **Functions (1)**\n* [t_open](#t_open)"));
+    }
+}
@@ -1,90 +1,184 @@
-use std::cell::RefCell;
+use std::sync::Arc;
 
 use crate::{
+    json::Json,
     page::{view::link::Link, Renderer},
-    utils::IntoMd,
+    symbols::escape_html,
+    template::Templates,
 };
 
+// link with name of group it is nested in.
+type GroupLink = (Link, Option<String>);
+
 pub(crate) struct SidePanel {
-    objetcs_name: RefCell<Vec<Link>>,
-    functions: RefCell<Vec<Link>>,
-    includes: RefCell<Vec<Link>>,
+    objetcs_name: Vec<(Link, Option<String>)>,
+    functions: Vec<(Link, Option<String>)>,
+    includes: Vec<(Link, Option<String>)>,
+    groups: Vec<String>,
+    templates: Arc<Templates>,
 }
 
 impl SidePanel {
     pub(crate) fn new() -> Self {
         SidePanel {
-            objetcs_name: RefCell::new(vec![]),
-            functions: RefCell::new(vec![]),
-            includes: RefCell::new(vec![]),
+            objetcs_name: vec![],
+            functions: vec![],
+            includes: vec![],
+            groups: vec![],
+            templates: Templates::shared(),
         }
     }
 
-    pub(crate) fn add_obj(&self, l: Link) {
-        self.objetcs_name.borrow_mut().push(l);
+    pub(crate) fn set_templates(&mut self, templates: Arc<Templates>) {
+        self.templates = templates;
     }
 
-    // pub(crate) fn get_objs(&self) -> Vec<Link> {
-    //     let a = self.objetcs_name.borrow();
-    //     a.clone()
-    // }
-    pub(crate) fn add_fun(&self, l: Link) {
-        self.functions.borrow_mut().push(l);
+    pub(crate) fn add_group(&mut self, name: &str) {
+        self.groups.push(String::from(name));
     }
 
-    // pub(crate) fn get_func(&self) -> Vec<Link> {
-    //     let a = self.functions.borrow();
-    //     a.clone()
-    // }
+    pub(crate) fn add_obj(&mut self, l: Link, group: Option<String>) {
+        self.objetcs_name.push((l, group));
+    }
 
-    pub(crate) fn add_includes(&self, l: Link) {
-        self.includes.borrow_mut().push(l);
+    pub(crate) fn add_fun(&mut self, l: Link, group: Option<String>) {
+        self.functions.push((l, group));
     }
 
-    // pub(crate) fn get_incl(&self) -> Vec<Link> {
-    //     let a = self.includes.borrow();
-    //     a.clone()
-    // }
-}
+    pub(crate) fn add_includes(&mut self, l: Link, group: Option<String>) {
+        self.includes.push((l, group));
+    }
 
-impl Renderer for SidePanel {
-    fn render(&self) -> String {
-        let obj = self.objetcs_name.borrow();
-        let obj_len = obj.len();
-        let obj = if obj_len > 0 {
-            obj.iter()
-                .map(|x| x.into_md())
-                .collect::<Vec<String>>()
-                .join("\n")
-        } else {
-            String::from("")
-        };
+    // sections with their links, empty ones are left out.
+    fn sections(&self) -> Vec<(&str, &[GroupLink])> {
+        [
+            ("Objects", &self.objetcs_name),
+            ("Functions", &self.functions),
+            ("Includes", &self.includes),
+        ]
+        .into_iter()
+        .filter(|(_, links)| !links.is_empty())
+        .map(|(title, links)| (title, links.as_slice()))
+        .collect()
+    }
+
+    // text of headings written by default sidebar template.
+    pub(crate) fn headings(&self) -> Vec<String> {
+        self.sections()
+            .iter()
+            .map(|(title, links)| format!("{} ({})", title, links.len()))
+            .collect()
+    }
 
-        let func = self.functions.borrow();
-        let func_len = func.len();
-        let func = if func_len > 0 {
-            func.iter()
-                .map(|x| x.into_md())
-                .collect::<Vec<String>>()
-                .join("\n")
-        } else {
-            String::from("")
+    // ungrouped links first, then links nested below their group name.
+    fn links(&self, links: &[(Link, Option<String>)]) -> Json {
+        let link = |l: &Link, nested: bool| {
+            let url = match l.is_page() {
+                true => String::from(l.get_url()),
+                false => format!("#{}", l.get_url().trim_start_matches('#')),
+            };
+            Json::obj(vec![
+                ("name", Json::str(l.get_name())),
+                ("url", Json::Str(url)),
+                ("group", Json::Bool(false)),
+                ("nested", Json::Bool(nested)),
+                ("deprecated", Json::Bool(l.is_deprecated())),
+            ])
         };
+        let mut entries = links
+            .iter()
+            .filter(|(_, g)| g.is_none())
+            .map(|(l, _)| link(l, false))
+            .collect::<Vec<Json>>();
 
-        let incl = self.includes.borrow();
-        let incl_len = incl.len();
-        let incl = if incl_len > 0 {
-            incl.iter()
-                .map(|x| x.into_md())
-                .collect::<Vec<String>>()
-                .join("\n")
-        } else {
-            String::from("")
+        self.groups.iter().for_each(|name| {
+            let nested = links
+                .iter()
+                .filter(|(_, g)| g.as_ref() == Some(name))
+                .map(|(l, _)| link(l, true))
+                .collect::<Vec<Json>>();
+            if !nested.is_empty() {
+                entries.push(Json::obj(vec![
+                    ("name", Json::str(name)),
+                    ("url", Json::str("")),
+                    ("group", Json::Bool(true)),
+                    ("nested", Json::Bool(false)),
+                    ("deprecated", Json::Bool(false)),
+                ]));
+                entries.extend(nested);
+            }
+        });
+
+        Json::Arr(entries)
+    }
+
+    // same list as markdown sidebar for html page.
+    // url of linked page is given by page_url, None when page is not generated.
+    pub(crate) fn render_html<F>(&self, page_url: F) -> String
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let item = |l: &Link| {
+            let name = match l.is_deprecated() {
+                true => format!("<del>{}</del>", escape_html(l.get_name())),
+                false => escape_html(l.get_name()),
+            };
+            let url = match l.is_page() {
+                true => page_url(l.get_url()),
+                false => Some(format!("#{}", l.get_url().trim_start_matches('#'))),
+            };
+            match url {
+                Some(x) => format!("<li><a href=\"{}\">{}</a></li>", x, name),
+                None => format!("<li>{}</li>", name),
+            }
+        };
+        let list = |links: &[(Link, Option<String>)]| {
+            let mut lines = links
+                .iter()
+                .filter(|(_, g)| g.is_none())
+                .map(|(l, _)| item(l))
+                .collect::<Vec<String>>();
+            self.groups.iter().for_each(|name| {
+                let nested = links
+                    .iter()
+                    .filter(|(_, g)| g.as_ref() == Some(name))
+                    .map(|(l, _)| item(l))
+                    .collect::<Vec<String>>();
+                if !nested.is_empty() {
+                    lines.push(format!(
+                        "<li><strong>{}</strong><ul>\n{}\n</ul></li>",
+                        escape_html(name),
+                        nested.join("\n")
+                    ));
+                }
+            });
+            format!("<ul>\n{}\n</ul>", lines.join("\n"))
         };
 
-        format!(
-            "#### **Objects ({})**\n{}\n#### **Functions ({})**\n{}\n#### **Includes ({})**\n{}",
-            obj_len, obj, func_len, func, incl_len, incl
+        self.sections()
+            .iter()
+            .map(|(title, links)| format!("<h4>{} ({})</h4>\n{}", title, links.len(), list(links)))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+impl Renderer for SidePanel {
+    fn render(&self) -> String {
+        let sections = self
+            .sections()
+            .iter()
+            .map(|(title, links)| {
+                Json::obj(vec![
+                    ("title", Json::str(title)),
+                    ("count", Json::Num(links.len() as f64)),
+                    ("links", self.links(links)),
+                ])
+            })
+            .collect::<Vec<Json>>();
+        self.templates.render(
+            "sidebar",
+            &Json::obj(vec![("sections", Json::Arr(sections))]),
         )
     }
 }
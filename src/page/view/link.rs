@@ -5,6 +5,7 @@ pub(crate) struct Link {
     name: String,
     url: String,
     go_page: bool,
+    deprecated: bool,
 }
 
 impl Link {
@@ -13,15 +14,39 @@ impl Link {
             name: String::from(name),
             url: String::from(url),
             go_page: open_page,
+            deprecated: false,
         }
     }
+
+    // item of link is deprecated, sidebar strike it through.
+    pub(crate) fn set_deprecated(&mut self, deprecated: bool) {
+        self.deprecated = deprecated;
+    }
+
+    pub(crate) fn is_deprecated(&self) -> bool {
+        self.deprecated
+    }
+
+    pub(crate) fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn get_url(&self) -> &str {
+        &self.url
+    }
+
+    // link open other page instead of anchor in the same page.
+    pub(crate) fn is_page(&self) -> bool {
+        self.go_page
+    }
 }
 
 impl IntoMd for Link {
     fn into_md(&self) -> String {
         match self.go_page {
+            true if self.url.is_empty() => format!("* {}", self.name),
             true => format!("* [{}]({})", self.name, self.url),
-            false => format!("* [{}](#{})", self.name, self.url),
+            false => format!("* [{}](#{})", self.name, self.url.trim_start_matches('#')),
         }
     }
 }
@@ -1,14 +1,33 @@
-use std::cell::RefCell;
-
 use link::Link;
 
-use crate::utils::{AnchorMd, IntoMd, TitleMd};
+use crate::{
+    config::Fields,
+    json::Json,
+    symbols::Linker,
+    template::Templates,
+    utils::{
+        c_function::CFunction,
+        c_includes::CIncludes,
+        c_object::{c_variable_field::CVariableField, CObject},
+        doc_tag::{parse_tags, tag_title, CommentLines, DocTag},
+        AnchorMd, IntoMd, TitleMd,
+    },
+};
 
 pub(crate) mod link;
 
+// everything the view of an item needs besides the item.
+pub(crate) struct ViewContext<'a> {
+    pub(crate) linker: Option<&'a Linker>,
+    pub(crate) templates: &'a Templates,
+    pub(crate) fields: Fields,
+}
+
 pub(crate) trait IntoViewAnchor {
-    fn into_view(&self) -> String;
+    #[allow(clippy::wrong_self_convention)]
+    fn into_view(&self, cx: &ViewContext) -> String;
     fn create_anchor(&self) -> Option<Link>;
+    fn view_rows(&self) -> ItemRows;
 }
 
 #[derive(Clone)]
@@ -16,8 +35,14 @@ pub(crate) struct FieldView<T>
 where
     T: IntoMd + TitleMd + AnchorMd + Clone,
 {
-    desc: RefCell<Option<String>>,
-    object: RefCell<T>,
+    desc: Option<String>,
+    object: T,
+    group: Option<String>,
+    private: bool,
+    line: usize,
+    anchor: Option<String>,
+    deprecated: Option<String>,
+    comment: CommentLines,
 }
 
 impl<T> FieldView<T>
@@ -26,46 +51,600 @@ where
 {
     pub(crate) fn new(desc: Option<String>, obj: T) -> Self {
         FieldView {
-            desc: RefCell::new(desc),
-            object: RefCell::new(obj),
+            desc,
+            object: obj,
+            group: None,
+            private: false,
+            line: 0,
+            anchor: None,
+            deprecated: None,
+            comment: CommentLines::new(),
         }
     }
 
+    pub(crate) fn set_line(&mut self, line: usize) {
+        self.line = line;
+    }
+
+    pub(crate) fn get_line(&self) -> usize {
+        self.line
+    }
+
+    pub(crate) fn set_comment(&mut self, comment: CommentLines) {
+        self.comment = comment;
+    }
+
+    pub(crate) fn get_comment(&self) -> CommentLines {
+        self.comment.clone()
+    }
+
+    // line of comment holding text, line of declaration when no comment has it.
+    pub(crate) fn line_of(&self, text: &str) -> usize {
+        self.comment.line_of(text, self.line)
+    }
+
+    // id of item heading in its page, unique among every heading of the page.
+    pub(crate) fn set_anchor(&mut self, anchor: &str) {
+        self.anchor = Some(String::from(anchor));
+    }
+
+    pub(crate) fn set_private(&mut self, private: bool) {
+        self.private = private;
+    }
+
+    pub(crate) fn is_private(&self) -> bool {
+        self.private
+    }
+
+    pub(crate) fn set_group(&mut self, group: Option<String>) {
+        self.group = group;
+    }
+
+    pub(crate) fn get_group(&self) -> Option<String> {
+        self.group.clone()
+    }
+
+    // message of `__attribute__((deprecated))` or `GX_DEPRECATED` marker of declaration,
+    // empty when it has none.
+    pub(crate) fn set_deprecated(&mut self, deprecated: Option<String>) {
+        self.deprecated = deprecated;
+    }
+
+    // reason of `@deprecated` tag, else message of marker. Some empty when item is
+    // deprecated without reason.
+    pub(crate) fn get_deprecated(&self) -> Option<String> {
+        let tag = self.desc.as_ref().and_then(|x| {
+            let (_, tags) = parse_tags(x);
+            tags.into_iter()
+                .find(|t| t.get_tag() == "deprecated")
+                .map(|t| t.get_text().to_string())
+        });
+        let found = [tag, self.deprecated.clone()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<String>>();
+        found
+            .iter()
+            .find(|x| !x.is_empty())
+            .or(found.first())
+            .cloned()
+    }
+
     // pub(crate) fn get_title(&self) -> Option<String> {
-    //     let a = self.subtitle.borrow();
+    //     let a = &self.subtitle;
     //     a.clone()
     // }
 
-    // pub(crate) fn get_desc(&self) -> Option<String> {
-    //     let a = self.desc.borrow();
-    //     a.clone()
-    // }
+    pub(crate) fn get_desc(&self) -> Option<String> {
+        let a = &self.desc;
+        a.clone()
+    }
 
-    // pub(crate) fn get_field(&self) -> T {
-    //     let a = self.field.borrow();
-    //     a.clone()
-    // }
+    pub(crate) fn get_field(&self) -> T {
+        let a = &self.object;
+        a.clone()
+    }
+
+    pub(crate) fn set_field(&mut self, obj: T) {
+        self.object = obj;
+    }
+}
+
+// rows of item tables shared by markdown and html view. type is C code and
+// text is markdown of doc comment, each view link and escape them its own way.
+#[derive(Default)]
+pub(crate) struct ItemRows {
+    pub(crate) fields: Vec<FieldRow>,
+    pub(crate) params: Vec<ParamRow>,
+    pub(crate) returns: Option<ReturnRow>,
+    pub(crate) tags: Vec<TagRow>,
 }
 
-impl<T> IntoViewAnchor for FieldView<T>
+pub(crate) struct FieldRow {
+    pub(crate) name: String,
+    pub(crate) ty: String,
+    // array dimensions like `[16]` or bit-field width like `: 3`
+    pub(crate) extent: String,
+    pub(crate) desc: String,
+}
+
+pub(crate) struct ParamRow {
+    pub(crate) name: String,
+    pub(crate) ty: String,
+    pub(crate) direction: &'static str,
+    pub(crate) desc: String,
+}
+
+pub(crate) struct ReturnRow {
+    pub(crate) ty: String,
+    pub(crate) desc: String,
+}
+
+// tags of the same kind next to each other share one row.
+pub(crate) struct TagRow {
+    pub(crate) tag: String,
+    pub(crate) values: Vec<DocTag>,
+}
+
+fn field_rows(fields: &[CVariableField]) -> Vec<FieldRow> {
+    fields
+        .iter()
+        .map(|f| FieldRow {
+            name: f.get_name(),
+            ty: f.get_unit(),
+            extent: match (f.get_array(), f.get_bits()) {
+                (Some(a), _) => a,
+                (None, Some(b)) => format!(": {}", b),
+                (None, None) => String::new(),
+            },
+            desc: f.get_desc().unwrap_or_default(),
+        })
+        .collect()
+}
+
+// tags kept below description, `@param`, `@return` and `@deprecated` have
+// their own place.
+fn tag_rows(tags: &[DocTag], func: bool) -> Vec<TagRow> {
+    let own = match func {
+        true => &["param", "return", "returns", "deprecated"][..],
+        false => &["deprecated"][..],
+    };
+    let mut rows: Vec<TagRow> = vec![];
+    for t in tags.iter().filter(|t| !own.contains(&t.get_tag())) {
+        match rows.last_mut() {
+            Some(row) if row.tag == t.get_tag() => row.values.push(t.clone()),
+            _ => rows.push(TagRow {
+                tag: String::from(t.get_tag()),
+                values: vec![t.clone()],
+            }),
+        }
+    }
+    rows
+}
+
+// direction given by `@param[in]`, `[out]` or `[in,out]`, else guessed from type:
+// value and pointer to const are only read.
+fn direction(option: Option<&str>, ty: &str) -> &'static str {
+    // qualifiers of pointed data are between the last two `*`,
+    // e.g. `char const *` but not `const char **`
+    let pointee = ty.rsplit('*').nth(1);
+    let is_const = |x: &str| {
+        x.split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .any(|w| w == "const")
+    };
+    match option.map(|x| x.replace(' ', "")).as_deref() {
+        Some("in") => "in",
+        Some("out") => "out",
+        Some("in,out") | Some("inout") | Some("out,in") => "in/out",
+        _ => match pointee {
+            None => "in",
+            Some(x) if is_const(x) => "in",
+            // pointer to mutable data can be read and written
+            Some(_) => "in/out",
+        },
+    }
+}
+
+// rows of parameters table and return of function, from its declaration
+// and its `@param` and `@return` tags. `@param` of unknown name is left out,
+// lint report it.
+fn signature_rows(func: &CFunction, tags: &[DocTag]) -> (Vec<ParamRow>, Option<ReturnRow>) {
+    let params = func
+        .get_params()
+        .iter()
+        .filter(|p| p.get_unit() != "void" || p.get_name().is_some())
+        .map(|p| {
+            let name = p.get_name().unwrap_or_default();
+            let tag = tags
+                .iter()
+                .filter(|t| t.get_tag() == "param")
+                .find(|t| !name.is_empty() && t.get_arg() == Some(name.as_str()));
+            let ty = p.signature();
+            ParamRow {
+                direction: direction(tag.and_then(|t| t.get_option()), &ty),
+                desc: String::from(tag.map(|t| t.get_rest()).unwrap_or_default()),
+                name,
+                ty,
+            }
+        })
+        .collect::<Vec<ParamRow>>();
+
+    let returns = tags
+        .iter()
+        .filter(|t| t.get_tag() == "return" || t.get_tag() == "returns")
+        .map(|t| t.get_text())
+        .collect::<Vec<&str>>();
+    let returns = match func.is_void() && returns.is_empty() {
+        true => None,
+        false => Some(ReturnRow {
+            ty: func.get_return(),
+            desc: returns.join(" "),
+        }),
+    };
+    (params, returns)
+}
+
+impl<T> FieldView<T>
 where
     T: IntoMd + TitleMd + AnchorMd + Clone,
 {
-    fn into_view(&self) -> String {
-        let object = self.object.borrow().into_md();
-        let code_obj = format!("\n\t```c\n{}\n\t```\n", &object);
+    // description without tags and rows of item tables: parameters and return
+    // of function, fields of struct and tags kept below description.
+    fn rows(
+        &self,
+        func: Option<&CFunction>,
+        fields: &[CVariableField],
+    ) -> (Option<String>, ItemRows) {
+        let (desc, tags) = match self.desc.as_ref() {
+            Some(x) => {
+                let (desc, tags) = parse_tags(x);
+                // description of function always lose its tags to its tables
+                match tags.is_empty() && func.is_none() {
+                    true => (Some(x.clone()), tags),
+                    false => (Some(desc), tags),
+                }
+            }
+            None => (None, vec![]),
+        };
+        let (params, returns) = match func {
+            Some(f) => signature_rows(f, &tags),
+            None => (vec![], None),
+        };
+        let rows = ItemRows {
+            fields: field_rows(fields),
+            params,
+            returns,
+            tags: tag_rows(&tags, func.is_some()),
+        };
+        (desc, rows)
+    }
 
-        let heading = format!("#### **{}**", &self.object.borrow().create_title());
+    // view of any item, function give its parameters table and struct its fields.
+    fn view(
+        &self,
+        cx: &ViewContext,
+        func: Option<&CFunction>,
+        fields: &[CVariableField],
+    ) -> String {
+        let linker = cx.linker;
+        let object = self.object.into_md();
+        let title = self.object.create_title();
+        let linked = match linker.and_then(|l| l.link_code(&object, &title)) {
+            Some(x) => String::from(x.strip_prefix('\t').unwrap_or(&x)),
+            None => String::new(),
+        };
+
+        // explicit anchor when page share its document with other page
+        let anchor = match (self.item_anchor(), linker) {
+            (Some(a), Some(l)) if !a.is_page() => l.anchor(a.get_url()),
+            _ => None,
+        };
 
-        let desc = self.desc.borrow();
-        let desc = match desc.as_ref() {
-            Some(x) => format!("\n\n\t{}", x),
+        let text = |x: &str| match linker {
+            Some(l) => l.link_text(&l.link_intra(x, &|t| self.line_of(t)), &title),
+            None => String::from(x),
+        };
+        let cell = |x: &str| x.replace('|', "\\|");
+        let code = |x: &str| match linker.and_then(|l| l.link_code(x, &title)) {
+            Some(x) => format!("<code>{}</code>", x),
+            None => format!("`{}`", x),
+        };
+        let (desc, rows) = self.rows(func, fields);
+
+        let deprecated = self.get_deprecated();
+        let deprecated_reason = deprecated.as_deref().map(text).unwrap_or_default();
+        let desc = match desc.as_ref().filter(|x| !x.is_empty()) {
+            Some(x) => text(x),
             None => String::new(),
         };
-        format!("* {}{}{}", heading, code_obj, desc)
+        let fields = match cx.fields {
+            Fields::Code => vec![],
+            Fields::Table | Fields::Both => rows
+                .fields
+                .iter()
+                .map(|f| {
+                    let extent = match f.extent.is_empty() {
+                        true => String::new(),
+                        false => format!("`{}`", f.extent),
+                    };
+                    Json::obj(vec![
+                        ("name", Json::str(&f.name)),
+                        ("type", Json::Str(cell(&code(&f.ty)))),
+                        ("extent", Json::Str(extent)),
+                        ("desc", Json::Str(cell(&text(&f.desc)))),
+                    ])
+                })
+                .collect(),
+        };
+        let params = rows
+            .params
+            .iter()
+            .map(|p| {
+                Json::obj(vec![
+                    ("name", Json::str(&p.name)),
+                    ("type", Json::Str(cell(&code(&p.ty)))),
+                    ("direction", Json::str(p.direction)),
+                    ("desc", Json::Str(cell(&text(&p.desc)))),
+                ])
+            })
+            .collect::<Vec<Json>>();
+        let returns = match rows.returns.as_ref() {
+            Some(r) => Json::obj(vec![
+                ("type", Json::Str(code(&r.ty))),
+                ("desc", Json::Str(cell(&text(&r.desc)))),
+            ]),
+            None => Json::Null,
+        };
+        // `@see` names are linked, other tags keep their text
+        let tags = rows
+            .tags
+            .iter()
+            .map(|row| {
+                let values = row
+                    .values
+                    .iter()
+                    .map(|t| match t.get_tag() {
+                        "see" => {
+                            let name = t.get_arg().unwrap_or_default();
+                            let code = format!("`{}`", name);
+                            let name = match linker.and_then(|l| l.symbol_url(name)) {
+                                Some(x) => format!("[{}]({})", code, x),
+                                None => code,
+                            };
+                            format!("{} {}", name, text(t.get_rest()))
+                                .trim_end()
+                                .to_string()
+                        }
+                        _ => text(t.get_text()),
+                    })
+                    .collect::<Vec<String>>();
+                let sep = match row.tag.as_str() {
+                    "see" => ", ",
+                    _ => " ",
+                };
+                Json::obj(vec![
+                    ("title", Json::Str(tag_title(&row.tag))),
+                    ("text", Json::Str(values.join(sep))),
+                ])
+            })
+            .collect::<Vec<Json>>();
+        // items of any file taking, returning or embedding this type
+        let used_by = match (self.item_anchor(), linker) {
+            (Some(a), Some(l)) if !a.is_page() => l
+                .used_by(a.get_url().trim_start_matches('#'))
+                .iter()
+                .map(|(name, url, file)| {
+                    Json::obj(vec![
+                        ("name", Json::str(name)),
+                        ("url", Json::str(url)),
+                        ("file", Json::str(file)),
+                    ])
+                })
+                .collect(),
+            _ => vec![],
+        };
+        // declaration is still shown when table has nothing to show
+        let show_code = cx.fields != Fields::Table || fields.is_empty();
+        let context = Json::obj(vec![
+            ("title", Json::str(&title)),
+            ("anchor", Json::str(&anchor.unwrap_or_default())),
+            ("code", Json::str(&object)),
+            ("linked_code", Json::Str(linked)),
+            ("deprecated", Json::Bool(deprecated.is_some())),
+            ("deprecated_reason", Json::Str(deprecated_reason)),
+            ("desc", Json::Str(desc)),
+            ("group", Json::str(&self.group.clone().unwrap_or_default())),
+            ("private", Json::Bool(self.private)),
+            ("show_code", Json::Bool(show_code)),
+            ("fields", Json::Arr(fields)),
+            ("params", Json::Arr(params)),
+            ("returns", returns),
+            ("tags", Json::Arr(tags)),
+            ("used_by", Json::Arr(used_by)),
+        ]);
+        cx.templates.render("item", &context)
+    }
+
+    fn item_anchor(&self) -> Option<Link> {
+        let link = self.object.create_anchor()?;
+        match (link.is_page(), self.anchor.as_ref()) {
+            (false, Some(x)) => Some(Link::new(link.get_name(), &format!("#{}", x), false)),
+            _ => Some(link),
+        }
+    }
+}
+
+impl IntoViewAnchor for FieldView<CObject> {
+    fn into_view(&self, cx: &ViewContext) -> String {
+        self.view(cx, None, &self.object.get_fields())
+    }
+
+    fn create_anchor(&self) -> Option<Link> {
+        self.item_anchor()
+    }
+
+    fn view_rows(&self) -> ItemRows {
+        self.rows(None, &self.object.get_fields()).1
+    }
+}
+
+impl IntoViewAnchor for FieldView<CFunction> {
+    fn into_view(&self, cx: &ViewContext) -> String {
+        self.view(cx, Some(&self.object), &[])
+    }
+
+    fn create_anchor(&self) -> Option<Link> {
+        self.item_anchor()
+    }
+
+    fn view_rows(&self) -> ItemRows {
+        self.rows(Some(&self.object), &[]).1
+    }
+}
+
+impl IntoViewAnchor for FieldView<CIncludes> {
+    // include is a link to page of the header, listed outside of code block so
+    // it stay clickable.
+    fn into_view(&self, cx: &ViewContext) -> String {
+        let title = self.object.create_title();
+        let desc = match (self.desc.as_ref(), cx.linker) {
+            (Some(x), Some(l)) => l.link_text(&l.link_intra(x, &|t| self.line_of(t)), &title),
+            (Some(x), None) => x.clone(),
+            (None, _) => String::new(),
+        };
+        let context = Json::obj(vec![
+            ("include", Json::Bool(true)),
+            ("title", Json::str(&title)),
+            ("code", Json::str(&self.object.into_md())),
+            ("desc", Json::Str(desc)),
+            ("private", Json::Bool(self.private)),
+        ]);
+        cx.templates.render("item", &context)
     }
 
     fn create_anchor(&self) -> Option<Link> {
-        self.object.borrow().create_anchor()
+        self.item_anchor()
+    }
+
+    fn view_rows(&self) -> ItemRows {
+        self.rows(None, &[]).1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufRead;
+
+    use super::*;
+    use crate::{
+        config::Config,
+        parser::{parse_function, str_parser},
+        utils::c_impl::CImplDocs,
+    };
+
+    fn render(desc: &str, decl: &str) -> String {
+        let templates = Templates::shared();
+        let cx = ViewContext {
+            linker: None,
+            templates: &templates,
+            fields: Fields::Both,
+        };
+        let fv = FieldView::new(Some(String::from(desc)), parse_function(decl));
+        fv.into_view(&cx)
+    }
+
+    #[test]
+    fn direction_of_param() {
+        assert_eq!(direction(Some("in"), "int*"), "in");
+        assert_eq!(direction(Some("out"), "int*"), "out");
+        assert_eq!(direction(None, "int"), "in");
+        assert_eq!(direction(None, "const char*"), "in");
+        assert_eq!(direction(None, "char const *"), "in");
+        assert_eq!(direction(None, "const char * const *"), "in");
+        assert_eq!(direction(None, "char*"), "in/out");
+        assert_eq!(direction(None, "const char **"), "in/out");
+        assert_eq!(direction(None, "char *const"), "in/out");
+    }
+
+    #[test]
+    fn param_option_with_space() {
+        let md = render(
+            "fill it @param[in, out] buf the buffer @param[out] n count @param src text",
+            "int fill(char *buf, int *n, char const *src);",
+        );
+        assert!(
+            md.contains("| `buf` | `char*` | in/out | the buffer |"),
+            "{}",
+            md
+        );
+        assert!(md.contains("| `n` | `int*` | out | count |"), "{}", md);
+        assert!(
+            md.contains("| `src` | `char const*` | in | text |"),
+            "{}",
+            md
+        );
+        assert!(!md.contains("out]"), "{}", md);
+    }
+
+    #[test]
+    fn tags_below_description() {
+        let md = render(
+            "close it @param id handle @param nope unknown @return code @see buf_open @see other()",
+            "int buf_close(int id);",
+        );
+        assert!(md.contains("\tclose it\n"), "{}", md);
+        assert!(md.contains("| `id` | `int` | in | handle |"), "{}", md);
+        assert!(md.contains("**Returns** `int`: code"), "{}", md);
+        assert!(md.contains("**See also** `buf_open`, `other()`"), "{}", md);
+        assert!(!md.contains('@'), "{}", md);
+        assert!(!md.contains("nope"), "{}", md);
+    }
+
+    #[test]
+    fn other_tags_keep_text() {
+        let md = render(
+            "flush @note slow @note blocking @since 1.2",
+            "void flush(void);",
+        );
+        assert!(md.contains("**Note** slow blocking"), "{}", md);
+        assert!(md.contains("**Since** 1.2"), "{}", md);
+    }
+
+    #[test]
+    fn deprecated_callout() {
+        let md = render("old @deprecated use `open2`", "void open(void);");
+        assert!(md.contains("> ⚠️ **Deprecated**: use `open2`"), "{}", md);
+        assert!(!md.contains("@deprecated"), "{}", md);
+
+        let mut fv = FieldView::new(None, parse_function("void close(void);"));
+        fv.set_deprecated(Some(String::new()));
+        assert_eq!(fv.get_deprecated().as_deref(), Some(""));
+        fv.set_deprecated(Some(String::from("use release")));
+        assert_eq!(fv.get_deprecated().as_deref(), Some("use release"));
+    }
+
+    #[test]
+    fn line_of_comment() {
+        let args = ["gx_md", "-src", "src", "-o", "out", "-h", "t.h"];
+        let cfg = Config::from_args(args.iter().map(|x| x.to_string()).collect()).unwrap();
+        let text = concat!(
+            "//! a point\n",
+            "//! like [`nope`]\n",
+            "struct Pt {\n",
+            "    int x; //!< see [`gone`]\n",
+            "};\n",
+            "\n",
+            "//! move it\n",
+            "//! @param pt the [`Pt`]\n",
+            "void move(struct Pt *pt);\n",
+        );
+        let content = str_parser(text.as_bytes().lines(), &cfg, &CImplDocs::new());
+        let obj = &content.get_objects()[0];
+        assert_eq!(obj.line_of("[`nope`]"), 2);
+        assert_eq!(obj.line_of("[`gone`]"), 4);
+        assert_eq!(obj.line_of("[`missing`]"), 3);
+        let func = &content.get_func()[0];
+        assert_eq!(func.line_of("@param pt"), 8);
+        assert_eq!(func.line_of("[`nope`]"), 9);
     }
 }
@@ -1,15 +1,20 @@
 use std::{
-    fs::File,
-    io::{BufReader, Lines},
+    io::{BufRead, Lines},
     process::exit,
 };
 
 use crate::{
+    config::{Config, Diagram},
     file_writer::create_file_name,
-    page::{utils::content::Content, view::FieldView},
+    page::{content::Content, group::Group, view::FieldView},
     utils::{
-        c_function::CFunction, c_function_param::CFunctionParams, c_includes::CIncludes,
-        c_struct::CStruct, c_struct_field::CStructField, CommentMain,
+        c_function::CFunction,
+        c_function_param::CFunctionParams,
+        c_impl::{CImplDoc, CImplDocs},
+        c_includes::CIncludes,
+        c_object::{c_variable_field::CVariableField, CObject, CObjectType},
+        doc_tag::CommentLines,
+        CommentMain, TitleMd,
     },
 };
 
@@ -22,6 +27,7 @@ pub(crate) enum TypeC {
     Struct,
     Func,
     Inc,
+    Group,
     Unknown,
 }
 
@@ -39,56 +45,170 @@ impl TypeC {
     }
 }
 
-pub(crate) fn str_parser(lines: Lines<BufReader<File>>, home: &str) -> Content {
-    let main_comment = CommentMain::new();
-    let content: Content = Content::new();
+pub(crate) fn str_parser<B>(lines: Lines<B>, cfg: &Config, impls: &CImplDocs) -> Content
+where
+    B: BufRead,
+{
+    let home = cfg.home.as_str();
+    let mut main_comment = CommentMain::new();
+    let mut content: Content = Content::new();
+    content.set_show_private(cfg.include_private);
+    content.set_fields(cfg.fields);
+    content.set_diagram(cfg.class_diagram != Diagram::Off);
 
     let mut is_f_main = true;
 
     let mut temp_func: CFunction = CFunction::new();
-    let mut tem_str: CStruct = CStruct::new();
+    let mut tem_str: CObject = CObject::new();
     let mut temp_inc: CIncludes = CIncludes::new();
 
     let mut desc: String = String::new();
     let mut prev: TypeC = TypeC::Unknown;
 
     let mut str: String = String::new();
-    let mut title = String::new();
+    let mut group: Option<String> = None;
+    let mut private = false;
+    // message of deprecated marker found in current declaration
+    let mut deprecated: Option<String> = None;
 
-    for line in lines.flatten() {
+    let mut line_no: usize = 0;
+    let mut start: usize = 0;
+    // description of struct fields by field index, with its line
+    let mut field_docs: Vec<(usize, String, usize)> = vec![];
+    // lines of description and field comments of current declaration
+    let mut comment = CommentLines::new();
+
+    for line in lines.map_while(Result::ok) {
+        line_no += 1;
+        let in_struct = prev == TypeC::Struct || prev == TypeC::TypedefStruct;
+        let in_body = in_struct && str.contains('{');
+        // comment after `{` opening struct body is not part of declaration
+        let opens_body = !in_body
+            && (in_struct
+                || line.starts_with(TypeC::Struct.to_str())
+                || line.starts_with(TypeC::TypedefStruct.to_str()));
+        let line = match line.split_once("//") {
+            Some((code, _)) if opens_body && code.contains('{') => code.trim_end().to_string(),
+            _ => line,
+        };
+        let line = match in_body {
+            true => {
+                let trimmed = line.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                // `//! text` above field
+                if let Some(x) = trimmed.strip_prefix("//!") {
+                    let x = x.trim_start_matches('<').trim();
+                    field_docs.push((body_fields(&str), x.to_string(), line_no));
+                    continue;
+                }
+                match line.split_once("//") {
+                    // `//!< text` after field
+                    Some((code, comment)) => {
+                        if let Some(x) = comment.strip_prefix("!<") {
+                            let index = body_fields(&format!("{}{}", str, code));
+                            field_docs.push((
+                                index.saturating_sub(1),
+                                x.trim().to_string(),
+                                line_no,
+                            ));
+                        }
+                        // plain comment line, body is not over
+                        if code.trim().is_empty() {
+                            continue;
+                        }
+                        code.trim_end().to_string()
+                    }
+                    None => line,
+                }
+            }
+            false => line,
+        };
         // if length is 0
-        if line.len() <= 0 {
+        if line.is_empty() {
             if prev == TypeC::MainComment {
                 is_f_main = false;
             }
             prev = TypeC::Unknown;
             desc.clear();
+            field_docs.clear();
+            comment = CommentLines::new();
+            private = false;
+            deprecated = None;
             continue;
         }
 
         // if start with ///!
         if line.starts_with(TypeC::MainComment.to_str()) {
-            if is_f_main == false {
+            let text = line.get(5..).unwrap_or("");
+            if !is_f_main {
+                // `///! ## Name` outside main comment start a group
+                if let Some(x) = text.trim_start().strip_prefix("## ") {
+                    group = Some(x.trim().to_string());
+                    content.add_group(Group::new(x.trim()));
+                    prev = TypeC::Group;
+                    desc.clear();
+                }
                 continue;
             }
-            main_comment.append(&line[5..]);
+            main_comment.append(text);
             prev = TypeC::MainComment;
             continue;
         }
         // if start with //!
         else if line.starts_with(TypeC::Desc.to_str()) {
             is_f_main = false;
+            let text = line.get(4..).unwrap_or("").trim();
+            if let Some(x) = text.strip_prefix("@group ") {
+                group = Some(x.trim().to_string());
+                content.add_group(Group::new(x.trim()));
+                prev = TypeC::Group;
+                desc.clear();
+                continue;
+            }
+            if text == "@endgroup" {
+                group = None;
+                prev = TypeC::Unknown;
+                desc.clear();
+                continue;
+            }
+            if text == "@private" || text == "@internal" {
+                if prev != TypeC::Desc {
+                    desc.clear();
+                }
+                prev = TypeC::Desc;
+                private = true;
+                continue;
+            }
+            // description right below group marker is group intro
+            if prev == TypeC::Group {
+                content.append_group_intro(text);
+                continue;
+            }
             if prev != TypeC::Desc {
                 desc.clear();
             }
+            if desc.is_empty() {
+                comment = CommentLines::new();
+            }
             prev = TypeC::Desc;
             desc += " ";
-            desc += &line[4..].trim();
+            desc += text;
+            comment.push(line_no, text);
             continue;
         }
         // other
         else {
             is_f_main = false;
+            let line = match line.starts_with('#') || line.starts_with("//") {
+                true => line,
+                false => {
+                    let (code, marker) = strip_deprecated(&line);
+                    deprecated = marker.or(deprecated);
+                    code
+                }
+            };
             if line.starts_with(TypeC::Inc.to_str()) {
                 // include header must be in one line.
                 prev = TypeC::Inc;
@@ -99,11 +219,17 @@ pub(crate) fn str_parser(lines: Lines<BufReader<File>>, home: &str) -> Content {
                     str.clear();
                     continue;
                 }
+                if str.is_empty() {
+                    start = line_no;
+                }
                 str += line.as_str();
                 temp_inc = parse_inc(&str, home);
                 str.clear();
             } else if line.starts_with(TypeC::Typedef.to_str()) || prev == TypeC::Typedef {
                 prev = TypeC::Typedef;
+                if str.is_empty() {
+                    start = line_no;
+                }
                 str += line.as_str();
 
                 if line.contains("{") || line.contains("}") {
@@ -116,12 +242,15 @@ pub(crate) fn str_parser(lines: Lines<BufReader<File>>, home: &str) -> Content {
                     continue;
                 }
 
-                (title, tem_str) = parse_typedef(&str);
+                tem_str = parse_typedef(&str);
                 str.clear();
             } else if line.starts_with(TypeC::TypedefStruct.to_str())
                 || prev == TypeC::TypedefStruct
             {
                 prev = TypeC::TypedefStruct;
+                if str.is_empty() {
+                    start = line_no;
+                }
                 str += line.as_str();
                 if !line.contains("}") {
                     continue;
@@ -129,16 +258,27 @@ pub(crate) fn str_parser(lines: Lines<BufReader<File>>, home: &str) -> Content {
                 if !line.ends_with(";") {
                     continue;
                 }
-                (title, tem_str) = parse_ty_struct(&str);
+                tem_str = parse_ty_struct(&str);
+                field_docs.drain(..).for_each(|(i, d, l)| {
+                    comment.push(l, &d);
+                    tem_str.append_field_desc(i, &d);
+                });
                 str.clear()
             } else if line.starts_with(TypeC::Struct.to_str()) || prev == TypeC::Struct {
                 // struct
                 prev = TypeC::Struct;
+                if str.is_empty() {
+                    start = line_no;
+                }
                 str += line.as_str();
                 if !line.ends_with("};") {
                     continue;
                 }
-                (title, tem_str) = parse_cstruct(&str);
+                tem_str = parse_cstruct(&str);
+                field_docs.drain(..).for_each(|(i, d, l)| {
+                    comment.push(l, &d);
+                    tem_str.append_field_desc(i, &d);
+                });
                 str.clear();
             } else {
                 // function
@@ -149,11 +289,14 @@ pub(crate) fn str_parser(lines: Lines<BufReader<File>>, home: &str) -> Content {
                     continue;
                 }
                 prev = TypeC::Func;
+                if str.is_empty() {
+                    start = line_no;
+                }
                 str += line.as_str();
                 if !line.contains(");") {
                     continue;
                 }
-                (title, temp_func) = parse_function(&str);
+                temp_func = parse_function(&str);
                 str.clear();
             }
         }
@@ -166,37 +309,25 @@ pub(crate) fn str_parser(lines: Lines<BufReader<File>>, home: &str) -> Content {
                 } else {
                     Some(desc.clone().trim().to_string())
                 };
-                temp_inc.set_desc(d);
-                content.add_include(temp_inc.clone());
+                let mut fv = FieldView::new(d, temp_inc.clone());
+                fv.set_group(group.clone());
+                fv.set_line(start);
+                fv.set_comment(comment.clone());
+                fv.set_private(private || cfg.is_private_name(&temp_inc.get_name()));
+                content.add_include(fv);
                 prev = TypeC::Unknown;
             }
-            TypeC::Typedef => {
-                let d = if desc.is_empty() {
-                    None
-                } else {
-                    Some(desc.clone().trim().to_string())
-                };
-                let fv = FieldView::new(d, Some(title.clone()), tem_str.clone());
-                content.add_object(fv);
-                prev = TypeC::Unknown;
-            }
-            TypeC::TypedefStruct => {
-                let d = if desc.is_empty() {
-                    None
-                } else {
-                    Some(desc.clone().trim().to_string())
-                };
-                let fv = FieldView::new(d, Some(title.clone()), tem_str.clone());
-                content.add_object(fv);
-                prev = TypeC::Unknown;
-            }
-            TypeC::Struct => {
-                let d = if desc.is_empty() {
-                    None
-                } else {
-                    Some(desc.clone().trim().to_string())
-                };
-                let fv = FieldView::new(d, Some(title.clone()), tem_str.clone());
+            TypeC::Typedef | TypeC::TypedefStruct | TypeC::Struct => {
+                let mut fv = finish_object(
+                    cfg,
+                    &tem_str,
+                    &desc,
+                    group.clone(),
+                    start,
+                    &comment,
+                    private,
+                );
+                fv.set_deprecated(deprecated.clone());
                 content.add_object(fv);
                 prev = TypeC::Unknown;
             }
@@ -207,8 +338,15 @@ pub(crate) fn str_parser(lines: Lines<BufReader<File>>, home: &str) -> Content {
                     Some(desc.clone().trim().to_string())
                 };
 
+                let d = impls.merge(&temp_func, d);
+
                 //TODO! use name funcction as title. and show function as code below title.
-                let fv = FieldView::new(d, Some(title.clone()), temp_func.clone());
+                let mut fv = FieldView::new(d, temp_func.clone());
+                fv.set_group(group.clone());
+                fv.set_line(start);
+                fv.set_comment(comment.clone());
+                fv.set_private(private || cfg.is_private_name(&temp_func.get_name()));
+                fv.set_deprecated(deprecated.clone());
                 content.add_func(fv);
                 prev = TypeC::Unknown;
             }
@@ -218,16 +356,189 @@ pub(crate) fn str_parser(lines: Lines<BufReader<File>>, home: &str) -> Content {
         }
 
         desc.clear();
+        comment = CommentLines::new();
+        private = false;
+        deprecated = None;
     }
     content.set_main(Some(main_comment));
     content
 }
 
+// view of completed struct, union or typedef, private by marker or by its names.
+fn finish_object(
+    cfg: &Config,
+    obj: &CObject,
+    desc: &str,
+    group: Option<String>,
+    start: usize,
+    comment: &CommentLines,
+    private: bool,
+) -> FieldView<CObject> {
+    let d = match desc.trim() {
+        "" => None,
+        x => Some(String::from(x)),
+    };
+    let mut fv = FieldView::new(d, obj.clone());
+    fv.set_group(group);
+    fv.set_line(start);
+    fv.set_comment(comment.clone());
+    fv.set_private(
+        private || cfg.is_private_name(&obj.get_name()) || cfg.is_private_name(&obj.create_title()),
+    );
+    fv
+}
+
+// collect documented function definitions of .c file.
+pub(crate) fn impl_parser<B>(lines: Lines<B>, file: &str, impls: &mut CImplDocs)
+where
+    B: BufRead,
+{
+    let mut desc: String = String::new();
+    let mut str: String = String::new();
+    let mut depth: usize = 0;
+    let mut in_comment = false;
+
+    for line in lines.map_while(Result::ok) {
+        let comment_line = in_comment;
+        let code = strip_code(&line, &mut in_comment);
+        if depth > 0 {
+            depth += code.matches('{').count();
+            depth = depth.saturating_sub(code.matches('}').count());
+            continue;
+        }
+
+        if line.trim().is_empty() {
+            desc.clear();
+            str.clear();
+            continue;
+        }
+
+        if !comment_line && line.starts_with(TypeC::Desc.to_str()) {
+            desc += " ";
+            desc += line.get(4..).unwrap_or("").trim();
+            continue;
+        }
+
+        if !comment_line && (line.starts_with("//") || line.starts_with('#')) {
+            desc.clear();
+            str.clear();
+            continue;
+        }
+
+        // line of block comment only
+        if code.trim().is_empty() {
+            continue;
+        }
+
+        str += " ";
+        str += code.trim();
+
+        if let Some(x) = str.find('{') {
+            depth = str
+                .matches('{')
+                .count()
+                .saturating_sub(str.matches('}').count());
+            let head = str[..x].trim().to_string();
+            str.clear();
+            if !desc.is_empty() && is_definition(&head) {
+                let func = parse_function(&format!("{};", head));
+                impls.add(CImplDoc::new(func, desc.trim(), file));
+            }
+            desc.clear();
+        } else if str.ends_with(';') {
+            desc.clear();
+            str.clear();
+        }
+    }
+}
+
+// code of line without comments and with empty string and char literals, so
+// braces they hold are not counted. in_comment is kept between lines of `/* */`.
+fn strip_code(line: &str, in_comment: &mut bool) -> String {
+    let mut code = String::new();
+    let mut chars = line.chars().peekable();
+    let mut quote: Option<char> = None;
+    while let Some(c) = chars.next() {
+        if *in_comment {
+            if c == '*' && chars.peek() == Some(&'/') {
+                chars.next();
+                *in_comment = false;
+                code.push(' ');
+            }
+            continue;
+        }
+        if let Some(q) = quote {
+            if c == '\\' {
+                chars.next();
+            } else if c == q {
+                code.push(c);
+                quote = None;
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('/', Some('/')) => break,
+            ('/', Some('*')) => {
+                chars.next();
+                *in_comment = true;
+            }
+            ('"', _) | ('\'', _) => {
+                code.push(c);
+                quote = Some(c);
+            }
+            _ => code.push(c),
+        }
+    }
+    code
+}
+
+fn is_definition(head: &str) -> bool {
+    let open = match head.find('(') {
+        Some(x) => x,
+        None => return false,
+    };
+    head.ends_with(')') && head[..open].trim().contains(' ') && !head.contains('=')
+}
+
+// declaration without `__attribute__((deprecated))` or `GX_DEPRECATED` marker, with
+// message of marker, empty when marker has none.
+pub(crate) fn strip_deprecated(line: &str) -> (String, Option<String>) {
+    let found = ["__attribute__((deprecated", "GX_DEPRECATED"]
+        .iter()
+        .find_map(|m| line.find(m).map(|x| (x, *m)));
+    let (start, marker) = match found {
+        Some(x) => x,
+        None => return (String::from(line), None),
+    };
+    let rest = &line[start + marker.len()..];
+    // `GX_DEPRECATED("msg")` or `__attribute__((deprecated("msg")))`
+    let (args, tail) = match rest.strip_prefix('(').and_then(|x| x.split_once(')')) {
+        Some(x) => x,
+        None => ("", rest),
+    };
+    let tail = match marker {
+        "GX_DEPRECATED" => tail,
+        _ => tail.strip_prefix("))").unwrap_or(tail),
+    };
+    let message = args.trim().trim_matches('"').to_string();
+    let before = line[..start].trim_end();
+    let after = tail.trim_start();
+    let code = match (
+        before.is_empty(),
+        after.is_empty() || after.starts_with(';'),
+    ) {
+        (true, _) => String::from(after),
+        (false, true) => format!("{}{}", before, after),
+        (false, false) => format!("{} {}", before, after),
+    };
+    (code, Some(message))
+}
+
 pub(crate) fn parse_inc(str: &str, home: &str) -> CIncludes {
     let str = str.trim();
     let file = str.strip_prefix("#include").unwrap();
     let name = file.trim().replace("\"", "");
-    let inc = CIncludes::new();
+    let mut inc = CIncludes::new();
     inc.set_name(&name);
     let is_home = {
         let sep = if home.contains("/") { "/" } else { "\\" };
@@ -254,8 +565,9 @@ pub(crate) fn parse_inc(str: &str, home: &str) -> CIncludes {
     inc
 }
 
-pub(crate) fn parse_cstruct(str: &str) -> (String, CStruct) {
-    let c_struct = CStruct::new();
+pub(crate) fn parse_cstruct(str: &str) -> CObject {
+    let mut c_struct = CObject::new();
+    c_struct.set_obj_type(CObjectType::Struct);
     let str = str.trim().strip_prefix("struct").unwrap().trim();
     let name = &str[..str.find("{").unwrap()];
     let name = name.trim();
@@ -274,18 +586,65 @@ pub(crate) fn parse_cstruct(str: &str) -> (String, CStruct) {
         .split(";")
         .collect::<Vec<&str>>();
 
-    field.into_iter().for_each(|f| {
-        let f = f.trim();
-        let x = f.split(" ").collect::<Vec<&str>>();
-        let csf = CStructField::new(x[1], x[0]);
-        c_struct.add_field(csf);
-    });
+    field
+        .into_iter()
+        .for_each(|f| c_struct.add_field(parse_field(f)));
+
+    c_struct
+}
+
+// field declaration without `;`, e.g. `const char *name`, `int v[4]`,
+// `unsigned flag : 1` or `void (*cb)(int)`.
+pub(crate) fn parse_field(str: &str) -> CVariableField {
+    let str = str.split_whitespace().collect::<Vec<&str>>().join(" ");
+    let (decl, bits) = match str.rsplit_once(':') {
+        Some((d, b)) if !str.contains('(') => (d.trim(), Some(b.trim().to_string())),
+        _ => (str.as_str(), None),
+    };
+
+    // function pointer keep its type with name removed, e.g. `void (*)(int)`
+    if let Some(open) = decl.find("(*") {
+        let rest = &decl[open + 2..];
+        if let Some(close) = rest.find(')') {
+            let name = rest[..close].trim();
+            let unit = format!("{}(*){}", &decl[..open], &rest[close + 1..]);
+            return CVariableField::new(name, &unit);
+        }
+    }
+
+    let (decl, array) = match decl.find('[') {
+        Some(i) => (decl[..i].trim_end(), Some(decl[i..].replace(' ', ""))),
+        None => (decl, None),
+    };
+    let split = decl
+        .rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let name = &decl[split..];
+    let unit = decl[..split].trim_end();
+    // pointer mark belong to type: `char *`
+    let base = unit.trim_end_matches(['*', ' ']);
+    let stars = unit[base.len()..].matches('*').count();
+    let unit = match stars {
+        0 => String::from(base),
+        n => format!("{} {}", base, "*".repeat(n)),
+    };
+
+    let mut field = CVariableField::new(name, &unit);
+    field.set_array(array);
+    field.set_bits(bits);
+    field
+}
 
-    (format!("struct {}", name), c_struct)
+// number of fields ended so far in struct body collected in str.
+fn body_fields(str: &str) -> usize {
+    str.find('{')
+        .map(|i| str[i..].matches(';').count())
+        .unwrap_or(0)
 }
 
-pub(crate) fn parse_function(str: &str) -> (String, CFunction) {
-    let func = CFunction::new();
+pub(crate) fn parse_function(str: &str) -> CFunction {
+    let mut func = CFunction::new();
 
     let str = str.trim();
     let unit = &str[..str.find(" ").unwrap()];
@@ -305,22 +664,38 @@ pub(crate) fn parse_function(str: &str) -> (String, CFunction) {
     let params = params.split(",").collect::<Vec<&str>>();
 
     for p in params {
-        let p = p.trim();
-        let p = p.split(" ").collect::<Vec<&str>>();
-        let unit = p[0].trim();
-        let name = if p.len() < 2 {
-            None
-        } else {
-            Some(p[1].trim().to_string())
-        };
-        let cp = CFunctionParams::new(unit, name);
-        func.add_param(cp);
+        func.add_param(parse_param(p));
+    }
+    func
+}
+
+// words that make a type without being a name, `struct Obj` is a type without name.
+const TYPE_WORDS: [&str; 10] = [
+    "const", "volatile", "restrict", "struct", "union", "enum", "unsigned", "signed", "long",
+    "short",
+];
+
+// parameter declaration, its name keep pointer mark like `*obj` and array like `buf[4]`.
+pub(crate) fn parse_param(str: &str) -> CFunctionParams {
+    let field = parse_field(str);
+    let unit = field.get_unit();
+    let name = field.get_name();
+    if unit.contains("(*)") {
+        return CFunctionParams::new(&unit, Some(name));
+    }
+    let base = unit.trim_end_matches(['*', ' ']);
+    if name.is_empty() || base.is_empty() || base.split(' ').all(|w| TYPE_WORDS.contains(&w)) {
+        let ty = str.split_whitespace().collect::<Vec<&str>>().join(" ");
+        return CFunctionParams::new(&ty, None);
     }
-    (String::from(name), func)
+    let stars = "*".repeat(unit[base.len()..].matches('*').count());
+    let array = field.get_array().unwrap_or_default();
+    CFunctionParams::new(base, Some(format!("{}{}{}", stars, name, array)))
 }
 
-pub(crate) fn parse_ty_struct(str: &str) -> (String, CStruct) {
-    let c_struct = CStruct::new();
+pub(crate) fn parse_ty_struct(str: &str) -> CObject {
+    let mut c_struct = CObject::new();
+    c_struct.set_obj_type(CObjectType::Struct);
     let str = str.trim();
     let str = str.strip_prefix("typedef").unwrap().trim_start();
     let str = str.strip_prefix("struct").unwrap().trim_start();
@@ -336,31 +711,330 @@ pub(crate) fn parse_ty_struct(str: &str) -> (String, CStruct) {
         .split(";")
         .collect::<Vec<&str>>();
 
-    field.into_iter().for_each(|f| {
-        let f = f.trim();
-        let x = f.split(" ").collect::<Vec<&str>>();
-        let csf = CStructField::new(x[0], x[1]);
-        c_struct.add_field(csf);
-    });
+    field
+        .into_iter()
+        .for_each(|f| c_struct.add_field(parse_field(f)));
 
     let alias = &str[1 + str.find("}").unwrap()..]
         .trim()
         .strip_suffix(";")
         .unwrap()
         .trim_end();
-    c_struct.set_alias(alias);
+    c_struct.set_alias(Some(alias.to_string()));
 
-    (alias.to_string(), c_struct)
+    c_struct
 }
 
-pub(crate) fn parse_typedef(str: &str) -> (String, CStruct) {
-    let cs = CStruct::new();
+pub(crate) fn parse_typedef(str: &str) -> CObject {
+    let mut cs = CObject::new();
+    cs.set_obj_type(CObjectType::Alias);
     let str = str.trim().strip_prefix("typedef").unwrap().trim();
-    let str = str.strip_prefix("struct").unwrap().trim();
-    let str = str.split(" ").collect::<Vec<&str>>();
-    let name = str[0].trim();
-    let alias = str[1].strip_suffix(";").unwrap().trim_end();
+    let (alias_of, str) = match str.split_once(' ') {
+        Some(("struct", x)) => (CObjectType::Struct, x.trim()),
+        Some(("union", x)) => (CObjectType::Union, x.trim()),
+        _ => (CObjectType::Unknown, str),
+    };
+    cs.set_alias_of(alias_of);
+    // alias is last word, e.g. `unsigned long size_t;`
+    let str = str.trim_end_matches(';').trim_end();
+    let (name, alias) = match str.rsplit_once(' ') {
+        Some((n, a)) => (n.trim(), a),
+        None => ("", str),
+    };
     cs.set_name(name);
-    cs.set_alias(alias);
-    (alias.to_string(), cs)
+    cs.set_alias(Some(alias.to_string()));
+    cs
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufRead;
+
+    use super::*;
+    use crate::utils::{c_object::c_variable_field::CVariableField, IntoMd};
+
+    fn config() -> Config {
+        let args = ["gx_md", "-src", "src", "-o", "out", "-h", "t.h"];
+        Config::from_args(args.iter().map(|x| x.to_string()).collect()).unwrap()
+    }
+
+    fn parse(text: &str) -> Content {
+        str_parser(text.as_bytes().lines(), &config(), &CImplDocs::new())
+    }
+
+    #[test]
+    fn impl_with_empty_doc_line() {
+        let mut impls = CImplDocs::new();
+        let text = "//! open it\n//!\n//! fast\nint open(int fd)\n{\n    return fd;\n}\n";
+        impl_parser(text.as_bytes().lines(), "/a.c", &mut impls);
+        assert_eq!(impls.len(), 1);
+        let func = parse_function("int open(int fd);");
+        let desc = impls.merge(&func, None).unwrap_or_default();
+        assert_eq!(
+            desc.split_whitespace().collect::<Vec<&str>>(),
+            ["open", "it", "fast"]
+        );
+    }
+
+    #[test]
+    fn impl_braces_in_comments_and_literals() {
+        let mut impls = CImplDocs::new();
+        let text = concat!(
+            "/* license {\n",
+            " * of file\n",
+            " */\n",
+            "\n",
+            "//! open it\n",
+            "int open(int fd) /* { */\n",
+            "{\n",
+            "    /* } */ char c = '}';\n",
+            "    const char *s = \"{ \\\" }}\"; // }\n",
+            "    return fd;\n",
+            "}\n",
+            "\n",
+            "//! close it\n",
+            "int close(int fd)\n",
+            "{\n",
+            "    return fd;\n",
+            "}\n",
+        );
+        impl_parser(text.as_bytes().lines(), "/a.c", &mut impls);
+        assert_eq!(impls.len(), 2);
+        let desc = impls.merge(&parse_function("int close(int fd);"), None);
+        assert_eq!(desc.as_deref(), Some("close it"));
+    }
+
+    #[test]
+    fn struct_body_with_comments() {
+        let content = parse(concat!(
+            "//! point\n",
+            "struct Pt { // x coordinate\n",
+            "    int x; //!< x value\n",
+            "    // plain comment\n",
+            "    //! y value\n",
+            "    int y;\n",
+            "};\n",
+            "//! use it\n",
+            "int use_pt(struct Pt *p);\n",
+        ));
+        let objects = content.get_objects();
+        assert_eq!(objects.len(), 1);
+        let pt = objects[0].get_field();
+        assert_eq!(pt.create_title(), "struct Pt");
+        let fields = pt.get_fields();
+        let names = fields.iter().map(|f| f.get_name()).collect::<Vec<String>>();
+        assert_eq!(names, ["x", "y"]);
+        let docs = fields
+            .iter()
+            .map(CVariableField::get_desc)
+            .collect::<Vec<Option<String>>>();
+        assert_eq!(docs, [Some("x value".into()), Some("y value".into())]);
+
+        let func = content.get_func();
+        assert_eq!(func.len(), 1);
+        assert_eq!(func[0].get_field().get_name(), "use_pt");
+    }
+
+    #[test]
+    fn deprecated_markers() {
+        let some = |x: &str| Some(String::from(x));
+        assert_eq!(
+            strip_deprecated("int open(void);"),
+            (String::from("int open(void);"), None)
+        );
+        assert_eq!(
+            strip_deprecated("GX_DEPRECATED(\"use open2\") int open(void);"),
+            (String::from("int open(void);"), some("use open2"))
+        );
+        assert_eq!(
+            strip_deprecated("int GX_DEPRECATED old(void);"),
+            (String::from("int old(void);"), some(""))
+        );
+        assert_eq!(
+            strip_deprecated("int open(void) __attribute__((deprecated));"),
+            (String::from("int open(void);"), some(""))
+        );
+        assert_eq!(
+            strip_deprecated("int old(int a) __attribute__((deprecated(\"use new\")));"),
+            (String::from("int old(int a);"), some("use new"))
+        );
+        assert_eq!(
+            strip_deprecated("GX_DEPRECATED struct Obj {"),
+            (String::from("struct Obj {"), some(""))
+        );
+    }
+
+    #[test]
+    fn deprecated_declarations() {
+        let content = parse(concat!(
+            "//! old open\n",
+            "GX_DEPRECATED(\"use open2\")\n",
+            "int open(int fd);\n",
+            "\n",
+            "//! new open\n",
+            "int open2(int fd);\n",
+            "\n",
+            "//! point\n",
+            "struct Pt {\n",
+            "    int x;\n",
+            "} __attribute__((deprecated));\n",
+        ));
+        let funcs = content.get_func();
+        assert_eq!(funcs.len(), 2);
+        assert_eq!(funcs[0].get_field().get_name(), "open");
+        assert_eq!(funcs[0].get_deprecated().as_deref(), Some("use open2"));
+        assert_eq!(funcs[1].get_deprecated(), None);
+        let objects = content.get_objects();
+        assert_eq!(objects.len(), 1);
+        assert_eq!(objects[0].get_deprecated().as_deref(), Some(""));
+    }
+
+    #[test]
+    fn groups() {
+        let content = parse(concat!(
+            "///! the header\n",
+            "\n",
+            "//! @group Files\n",
+            "//! open and close\n",
+            "\n",
+            "//! open\n",
+            "int open(int fd);\n",
+            "\n",
+            "//! a point\n",
+            "struct Pt {\n",
+            "    int x;\n",
+            "};\n",
+            "//! @endgroup\n",
+            "\n",
+            "//! size\n",
+            "int size(void);\n",
+            "\n",
+            "///! ## Memory\n",
+            "\n",
+            "//! alloc\n",
+            "int alloc(int n);\n",
+        ));
+        let groups = content.get_groups();
+        let names = groups.iter().map(|g| g.get_name()).collect::<Vec<&str>>();
+        assert_eq!(names, ["Files", "Memory"]);
+        assert_eq!(groups[0].get_intro().as_deref(), Some("open and close"));
+        assert_eq!(groups[1].get_intro(), None);
+
+        let funcs = content
+            .get_func()
+            .iter()
+            .map(|f| (f.get_field().get_name(), f.get_group()))
+            .collect::<Vec<_>>();
+        let group = |x: &str| Some(String::from(x));
+        assert_eq!(
+            funcs,
+            [
+                (String::from("open"), group("Files")),
+                (String::from("size"), None),
+                (String::from("alloc"), group("Memory")),
+            ]
+        );
+        assert_eq!(content.get_objects()[0].get_group(), group("Files"));
+        // main comment is not part of group intro
+        assert!(content.get_main().is_some());
+    }
+
+    fn private_names(content: &Content) -> Vec<(String, bool)> {
+        let mut names = content
+            .get_objects()
+            .iter()
+            .map(|o| (o.get_field().create_title(), o.is_private()))
+            .collect::<Vec<_>>();
+        names.extend(
+            content
+                .get_func()
+                .iter()
+                .map(|f| (f.get_field().get_name(), f.is_private())),
+        );
+        names
+    }
+
+    #[test]
+    fn private_markers() {
+        let content = parse(concat!(
+            "//! hidden\n",
+            "//! @private\n",
+            "int hidden(int fd);\n",
+            "\n",
+            "//! @internal\n",
+            "//! state\n",
+            "struct State {\n",
+            "    int x;\n",
+            "};\n",
+            "\n",
+            "//! open\n",
+            "int open(int fd);\n",
+        ));
+        assert_eq!(
+            private_names(&content),
+            [
+                (String::from("struct State"), true),
+                (String::from("hidden"), true),
+                (String::from("open"), false),
+            ]
+        );
+        // marker is not part of description
+        let funcs = content.get_func();
+        assert_eq!(funcs[0].get_desc().as_deref(), Some("hidden"));
+        assert_eq!(
+            content.get_objects()[0].get_desc().as_deref(),
+            Some("state")
+        );
+    }
+
+    #[test]
+    fn private_patterns() {
+        let args = [
+            "gx_md", "-src", "src", "-o", "out", "-h", "t.h", "-private", "_gx_*", "-private",
+            "_*_",
+        ];
+        let cfg = Config::from_args(args.iter().map(|x| x.to_string()).collect()).unwrap();
+        let text = concat!(
+            "//! reset\n",
+            "void _gx_internal_reset(void);\n",
+            "\n",
+            "//! object\n",
+            "typedef struct _Obj_ {\n",
+            "    int x;\n",
+            "} Obj;\n",
+            "\n",
+            "//! open\n",
+            "int gx_open(int fd);\n",
+        );
+        let content = str_parser(text.as_bytes().lines(), &cfg, &CImplDocs::new());
+        assert_eq!(
+            private_names(&content),
+            [
+                (String::from("Obj"), true),
+                (String::from("_gx_internal_reset"), true),
+                (String::from("gx_open"), false),
+            ]
+        );
+        // without pattern nothing is private
+        assert!(private_names(&parse(text)).iter().all(|(_, p)| !p));
+    }
+
+    #[test]
+    fn typedef_keeps_keyword() {
+        for (decl, md) in [
+            ("typedef struct Obj Jbo;", "\ttypedef struct Obj Jbo;"),
+            ("typedef union Val Lav;", "\ttypedef union Val Lav;"),
+            (
+                "typedef unsigned long size;",
+                "\ttypedef unsigned long size;",
+            ),
+        ] {
+            let obj = parse_typedef(decl);
+            assert_eq!(obj.into_md(), md);
+        }
+        assert_eq!(
+            parse_typedef("typedef union Val Lav;").create_title(),
+            "Lav"
+        );
+    }
 }
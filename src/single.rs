@@ -0,0 +1,226 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::Arc,
+};
+
+use crate::{
+    config::Config,
+    deprecated::deprecated_md,
+    error::Error,
+    file_writer::{tree_key, write_file, PageFile},
+    index::index_md,
+    ir::DocProject,
+    page::Page,
+    symbols::{Linker, SymbolTable},
+    template::Templates,
+};
+
+// document holding every page.
+const SINGLE_FILE: &str = "API.md";
+// anchor of symbol index, last section of the document.
+const INDEX_ANCHOR: &str = "symbol-index";
+const DEPRECATED_ANCHOR: &str = "deprecated-symbols";
+
+// anchor of header section, e.g. `sub-t-h` for `/sub/t.h`.
+fn file_anchor(path: &str, taken: &mut HashSet<String>) -> String {
+    let anchor = path
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_ascii_lowercase(),
+            false => '-',
+        })
+        .collect::<String>();
+    let anchor = anchor
+        .split('-')
+        .filter(|x| !x.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-");
+    let mut unique = anchor.clone();
+    let mut n = 1;
+    while !taken.insert(unique.clone()) {
+        unique = format!("{}-{}", anchor, n);
+        n += 1;
+    }
+    unique
+}
+
+// write every page into one markdown document with table of contents.
+// anchor of items is prefixed by anchor of its header so it stay unique.
+pub(crate) fn write_single(
+    cfg: &Config,
+    pages: Vec<PageFile>,
+    templates: Arc<Templates>,
+) -> Result<(), Error> {
+    let mut pages = pages;
+    pages.sort_by(|a, b| {
+        let a = a.page.get_path_src();
+        let b = b.page.get_path_src();
+        tree_key(&a).cmp(&tree_key(&b))
+    });
+
+    let mut taken = HashSet::new();
+    let anchors = pages
+        .iter()
+        .map(|x| file_anchor(&x.page.get_path_src(), &mut taken))
+        .collect::<Vec<String>>();
+    // wiki url used by include to section of the header
+    let urls = pages
+        .iter()
+        .zip(&anchors)
+        .map(|(x, a)| (x.page.get_url(), a.clone()))
+        .collect::<HashMap<String, String>>();
+
+    let mut project =
+        DocProject::from_pages(&pages.iter().map(|x| &x.page).collect::<Vec<&Page>>());
+    project
+        .files
+        .iter_mut()
+        .for_each(|f| f.url = String::from(SINGLE_FILE));
+
+    println!(":> Building symbol table.");
+    let symbols = project
+        .files
+        .iter()
+        .zip(&anchors)
+        .map(|(f, a)| {
+            let mut symbols = SymbolTable::file_symbols(f, cfg.include_private);
+            symbols
+                .iter_mut()
+                .for_each(|(_, s)| s.anchor = format!("{}-{}", a, s.anchor));
+            symbols
+        })
+        .collect::<Vec<_>>();
+    let table = Arc::new(SymbolTable::from_symbols(&symbols));
+
+    let mut sections = vec![];
+    for (pf, anchor) in pages.iter_mut().zip(&anchors) {
+        let src = pf.page.get_path_src();
+        println!(":> Writing documentation of file: {}.", &src);
+        let mut linker = Linker::new(table.clone(), SINGLE_FILE, &src);
+        linker.set_anchor_prefix(&format!("{}-", anchor));
+        pf.page.set_linker(Some(linker));
+        pf.page.set_templates(templates.clone());
+        pf.page
+            .map_include_url(|url| urls.get(url).map(|x| format!("#{}", x)));
+        pf.page.set_toc(true);
+        sections.push(format!(
+            "<a id=\"{}\"></a>\n\n{}",
+            anchor,
+            pf.page.render_content()
+        ));
+    }
+
+    let files = symbols.iter().collect::<Vec<_>>();
+    sections.push(format!(
+        "<a id=\"{}\"></a>\n\n{}",
+        INDEX_ANCHOR,
+        index_md(&files, |s| format!("#{}", s.anchor)).trim_end()
+    ));
+    sections.push(format!(
+        "<a id=\"{}\"></a>\n\n{}",
+        DEPRECATED_ANCHOR,
+        deprecated_md(&files, |s| format!("#{}", s.anchor)).trim_end()
+    ));
+
+    let doc = format!(
+        "# Contents\n\n{}\n* [Index](#{})\n* [Deprecated](#{})\n\n---\n\n{}\n",
+        contents(&pages, &anchors),
+        INDEX_ANCHOR,
+        DEPRECATED_ANCHOR,
+        sections.join("\n\n---\n\n")
+    );
+    let out = Path::new(&cfg.out).join(SINGLE_FILE);
+    println!(":> Writing single documentation {}.", out.display());
+    write_file(&out.to_string_lossy(), &doc)
+        .map_err(|e| Error::failed(7, "Writing single documentation", e))
+}
+
+// header tree with link to section of every header, pages are already in tree order.
+fn contents(pages: &[PageFile], anchors: &[String]) -> String {
+    let mut lines = vec![];
+    let mut open: Vec<String> = vec![];
+    for (pf, anchor) in pages.iter().zip(anchors) {
+        let path = pf.page.get_path_src();
+        let (dirs, _) = tree_key(&path);
+        let common = open.iter().zip(&dirs).take_while(|(a, b)| a == b).count();
+        open.truncate(common);
+        for d in &dirs[common..] {
+            lines.push(format!("{}* **{}/**", "  ".repeat(open.len()), d));
+            open.push(d.to_string());
+        }
+        lines.push(format!(
+            "{}* [{}](#{})",
+            "  ".repeat(open.len()),
+            pf.page.get_title(),
+            anchor
+        ));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env::temp_dir, fs, io::BufRead};
+
+    use super::*;
+    use crate::{parser::str_parser, utils::c_impl::CImplDocs};
+
+    fn page(cfg: &Config, path: &str, url: &str, text: &str) -> PageFile {
+        let mut page = Page::new();
+        page.set_content(Some(str_parser(
+            text.as_bytes().lines(),
+            cfg,
+            &CImplDocs::new(),
+        )));
+        page.set_path_src(path);
+        page.set_url(url);
+        page.set_title(path.rsplit('/').next().unwrap_or_default());
+        PageFile {
+            page,
+            out_page: String::new(),
+            out_side: String::new(),
+        }
+    }
+
+    #[test]
+    fn colliding_anchors() {
+        let mut taken = HashSet::new();
+        assert_eq!(file_anchor("/sub/t.h", &mut taken), "sub-t-h");
+        assert_eq!(file_anchor("/sub-t.h", &mut taken), "sub-t-h-1");
+        assert_eq!(file_anchor("/Sub/T.h", &mut taken), "sub-t-h-2");
+        assert_eq!(file_anchor("/t.h", &mut taken), "t-h");
+    }
+
+    #[test]
+    fn rewritten_links() {
+        let dir = temp_dir().join(format!("gx_md_single_{}", std::process::id()));
+        let out = dir.to_string_lossy().to_string();
+        let args = ["gx_md", "-src", "src", "-o", &out, "-h", "home.h"];
+        let cfg = Config::from_args(args.iter().map(|x| x.to_string()).collect()).unwrap();
+        let pages = vec![
+            page(&cfg, "/t.h", "T", "//! open it\nint gx_open(void);\n"),
+            page(
+                &cfg,
+                "/b.h",
+                "B",
+                "#include \"t.h\"\n#include \"u.h\"\n\n//! close it\nvoid gx_close(void);\n",
+            ),
+        ];
+        assert!(write_single(&cfg, pages, Templates::shared()).is_ok());
+        let doc = fs::read_to_string(dir.join(SINGLE_FILE)).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(doc.contains("* [b.h](#b-h)\n* [t.h](#t-h)\n"), "{}", doc);
+        // include is a link outside of code block
+        assert!(doc.contains("\n* [t.h](#t-h)\n* `u.h`"), "{}", doc);
+        assert!(!doc.contains("```c\n[t.h]"), "{}", doc);
+        // no section for what the header does not have
+        assert!(!doc.contains("(0)**"), "{}", doc);
+        assert!(!doc.contains("Objects"), "{}", doc);
+        // index point to anchors prefixed by anchor of header
+        assert!(doc.contains("<a id=\"t-h-gx_open\"></a>"), "{}", doc);
+        assert!(doc.contains("[`gx_open`](#t-h-gx_open)"), "{}", doc);
+        assert!(doc.contains("[`gx_close`](#b-h-gx_close)"), "{}", doc);
+    }
+}
@@ -0,0 +1,181 @@
+use std::collections::HashSet;
+
+// combining marks kept by github, std has no unicode category for them.
+fn is_mark(c: char) -> bool {
+    matches!(c,
+        '\u{0300}'..='\u{036f}'
+        | '\u{0483}'..='\u{0489}'
+        | '\u{0591}'..='\u{05bd}'
+        | '\u{064b}'..='\u{065f}'
+        | '\u{1ab0}'..='\u{1aff}'
+        | '\u{1dc0}'..='\u{1dff}'
+        | '\u{20d0}'..='\u{20ff}'
+        | '\u{3099}'..='\u{309a}'
+        | '\u{fe00}'..='\u{fe0f}'
+        | '\u{fe20}'..='\u{fe2f}')
+}
+
+// id github give to heading text: lower case, punctuation and symbol removed,
+// every space become a dash.
+pub(crate) fn slug(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            '-' | '_' => Some(c),
+            c if c.is_alphanumeric() || is_mark(c) => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+// text of markdown heading as rendered: heading mark, emphasis, code mark,
+// html tag and link url are removed.
+pub(crate) fn heading_text(md: &str) -> String {
+    let md = md.trim().trim_start_matches('#').trim();
+    let md = match md.trim_end_matches('#') {
+        x if x.ends_with(' ') => x.trim_end(),
+        _ => md,
+    };
+
+    let mut out = String::new();
+    let mut rest = md;
+    while let Some(c) = rest.chars().next() {
+        let after = &rest[c.len_utf8()..];
+        match c {
+            '<' => match after.find('>') {
+                Some(x) => rest = &after[x + 1..],
+                None => {
+                    out.push(c);
+                    rest = after;
+                }
+            },
+            '!' if after.starts_with('[') => rest = after,
+            '[' => match after.find("](") {
+                Some(x) if !after[..x].contains('[') => {
+                    out.push_str(&heading_text(&after[..x]));
+                    rest = match after[x..].find(')') {
+                        Some(end) => &after[x + end + 1..],
+                        None => "",
+                    };
+                }
+                _ => {
+                    out.push(c);
+                    rest = after;
+                }
+            },
+            '*' | '`' => rest = after,
+            '~' if after.starts_with('~') => rest = &after[1..],
+            _ => {
+                out.push(c);
+                rest = after;
+            }
+        }
+    }
+    out.trim().to_string()
+}
+
+// headings of markdown text, lines in code fence are not heading.
+pub(crate) fn headings(md: &str) -> Vec<String> {
+    let mut fence = false;
+    let mut out = vec![];
+    for line in md.lines() {
+        let line = line.trim_start();
+        if line.starts_with("```") || line.starts_with("~~~") {
+            fence = !fence;
+            continue;
+        }
+        let level = line.chars().take_while(|c| *c == '#').count();
+        if fence || level == 0 || level > 6 {
+            continue;
+        }
+        if line[level..].is_empty() || line[level..].starts_with([' ', '\t']) {
+            out.push(heading_text(line));
+        }
+    }
+    out
+}
+
+// ids of headings of one document, heading with already used id get `-1`, `-2`...
+pub(crate) struct Slugger {
+    taken: HashSet<String>,
+}
+
+impl Slugger {
+    pub(crate) fn new() -> Self {
+        Slugger {
+            taken: HashSet::new(),
+        }
+    }
+
+    pub(crate) fn slug(&mut self, text: &str) -> String {
+        let base = slug(text);
+        let mut id = base.clone();
+        let mut n = 0;
+        while !self.taken.insert(id.clone()) {
+            n += 1;
+            id = format!("{}-{}", base, n);
+        }
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii_heading() {
+        assert_eq!(slug("Hello World"), "hello-world");
+        assert_eq!(slug("struct Obj"), "struct-obj");
+        assert_eq!(slug("gx_buffer_append"), "gx_buffer_append");
+        assert_eq!(slug("test2.h"), "test2h");
+        assert_eq!(slug("Objects:"), "objects");
+        assert_eq!(slug("Objects (3)"), "objects-3");
+    }
+
+    #[test]
+    fn punctuation_and_symbols() {
+        assert_eq!(slug("C++ API"), "c-api");
+        assert_eq!(slug("Q&A"), "qa");
+        assert_eq!(slug("foo.bar()"), "foobar");
+        assert_eq!(slug("a - b"), "a---b");
+        assert_eq!(slug("what's new?"), "whats-new");
+        assert_eq!(slug("emoji 🎉 party"), "emoji--party");
+        assert_eq!(slug("1.2.3 release"), "123-release");
+    }
+
+    #[test]
+    fn unicode() {
+        assert_eq!(slug("Über Größe"), "über-größe");
+        assert_eq!(slug("日本語 見出し"), "日本語-見出し");
+        assert_eq!(slug("Привет Мир"), "привет-мир");
+        assert_eq!(slug("Cafe\u{301}"), "cafe\u{301}");
+    }
+
+    #[test]
+    fn duplicates() {
+        let mut s = Slugger::new();
+        assert_eq!(s.slug("Intro"), "intro");
+        assert_eq!(s.slug("Intro"), "intro-1");
+        assert_eq!(s.slug("intro"), "intro-2");
+        assert_eq!(s.slug("Intro-1"), "intro-1-1");
+        assert_eq!(s.slug("Other"), "other");
+    }
+
+    #[test]
+    fn heading_markup() {
+        assert_eq!(heading_text("#### **struct Obj**"), "struct Obj");
+        assert_eq!(heading_text("## `gx_open` and *more*"), "gx_open and more");
+        assert_eq!(heading_text("### [link](http://x.y) text"), "link text");
+        assert_eq!(heading_text("#### <a id=\"x\"></a>**Obj**"), "Obj");
+        assert_eq!(heading_text("# Title #"), "Title");
+        assert_eq!(heading_text("# C#"), "C#");
+    }
+
+    #[test]
+    fn markdown_headings() {
+        let md = "# One\ntext\n```\n# not heading\n```\n##Two\n### Three ###";
+        assert_eq!(headings(md), vec!["One", "Three"]);
+    }
+}
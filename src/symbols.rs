@@ -0,0 +1,592 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::ir::{DocDetail, DocFile, DocKind};
+
+// documented item that can be target of link.
+#[derive(Clone, PartialEq, Eq)]
+pub(crate) struct Symbol {
+    pub(crate) name: String,
+    pub(crate) kind: DocKind,
+    pub(crate) page: String,
+    pub(crate) anchor: String,
+    pub(crate) file: String,
+    // first sentence of description, shown in symbol index.
+    pub(crate) summary: String,
+    // type names of parameters, return and fields, to find what use a type.
+    pub(crate) uses: Vec<String>,
+    // reason given by `@deprecated` or deprecated marker, empty when none is given.
+    pub(crate) deprecated: Option<String>,
+}
+
+// every linkable symbol of the project, built before any page is rendered.
+pub(crate) struct SymbolTable {
+    // every definition of a name, in file order.
+    symbols: HashMap<String, Vec<Symbol>>,
+    // items using a type, by file and anchor of the type.
+    used_by: HashMap<(String, String), Vec<Symbol>>,
+}
+
+// names under which one item can be linked.
+pub(crate) type FileSymbols = Vec<(Vec<String>, Symbol)>;
+
+impl SymbolTable {
+    // symbols of every file, in file order so the first definition win outside the
+    // files defining a name.
+    pub(crate) fn from_symbols(files: &[FileSymbols]) -> Self {
+        let mut table = SymbolTable {
+            symbols: HashMap::new(),
+            used_by: HashMap::new(),
+        };
+
+        for (names, symbol) in files.iter().flatten() {
+            // every name is added, warning is given once per item
+            let duplicate = names
+                .iter()
+                .filter_map(|name| table.add(name, symbol.clone()))
+                .collect::<Vec<String>>()
+                .pop();
+            if let Some(x) = duplicate {
+                eprintln!(
+                    "::> Warning: {} is defined in {} and {}, links from other headers use the first one.",
+                    symbol.name, x, symbol.file
+                );
+            }
+        }
+
+        for (_, symbol) in files.iter().flatten() {
+            let targets = symbol
+                .uses
+                .iter()
+                .filter_map(|n| table.get_in(n, &symbol.file))
+                .filter(|t| t.kind == DocKind::Object && *t != symbol)
+                .map(|t| (t.file.clone(), t.anchor.clone()))
+                .collect::<Vec<(String, String)>>();
+            for key in targets {
+                let list = table.used_by.entry(key).or_default();
+                if !list.contains(symbol) {
+                    list.push(symbol.clone());
+                }
+            }
+        }
+        table
+    }
+
+    pub(crate) fn file_symbols(file: &DocFile, include_private: bool) -> FileSymbols {
+        let mut symbols = vec![];
+        for item in &file.items {
+            if item.private && !include_private {
+                continue;
+            }
+            let anchor = match item.anchor.as_ref() {
+                Some(x) => x,
+                None => continue,
+            };
+            let uses = match &item.detail {
+                DocDetail::Object { fields, .. } => fields.iter().map(|f| f.ty.as_str()).collect(),
+                DocDetail::Function { returns, params } => [returns.as_str()]
+                    .into_iter()
+                    .chain(params.iter().map(|p| p.ty.as_str()))
+                    .collect(),
+                DocDetail::Include { .. } => vec![],
+            };
+            let mut uses = uses
+                .into_iter()
+                .flat_map(type_names)
+                .collect::<Vec<String>>();
+            uses.sort();
+            uses.dedup();
+
+            let mut names = match &item.detail {
+                DocDetail::Object {
+                    object_type,
+                    name,
+                    alias,
+                    ..
+                } => {
+                    let mut names = vec![item.title.clone()];
+                    // alias only refer to struct defined somewhere else
+                    if object_type != "typedef" {
+                        names.push(name.clone());
+                        names.push(format!("{} {}", object_type, name));
+                    }
+                    if let Some(x) = alias {
+                        names.push(x.clone());
+                    }
+                    names
+                }
+                DocDetail::Function { .. } => vec![item.name.clone()],
+                DocDetail::Include { .. } => continue,
+            };
+            names.sort();
+            names.dedup();
+
+            let symbol = Symbol {
+                name: item.title.clone(),
+                kind: item.kind,
+                page: file.url.clone(),
+                anchor: anchor.clone(),
+                file: file.path.clone(),
+                summary: item
+                    .summary
+                    .as_deref()
+                    .map(first_sentence)
+                    .unwrap_or_default(),
+                uses,
+                deprecated: item.deprecated.as_deref().map(plain_links),
+            };
+            symbols.push((names, symbol));
+        }
+        symbols
+    }
+
+    // return file of first definition when name is already defined in other file.
+    // the same item added twice under one name is kept once.
+    fn add(&mut self, name: &str, symbol: Symbol) -> Option<String> {
+        let list = self.symbols.entry(String::from(name)).or_default();
+        if list
+            .iter()
+            .any(|x| x.file == symbol.file && x.anchor == symbol.anchor)
+        {
+            return None;
+        }
+        let first = list
+            .first()
+            .filter(|x| x.file != symbol.file)
+            .map(|x| x.file.clone());
+        list.push(symbol);
+        first
+    }
+
+    // definition of name in file, else first definition in the project.
+    pub(crate) fn get_in(&self, name: &str, file: &str) -> Option<&Symbol> {
+        let list = self.symbols.get(name)?;
+        list.iter().find(|s| s.file == file).or(list.first())
+    }
+
+    pub(crate) fn used_by(&self, file: &str, anchor: &str) -> &[Symbol] {
+        self.used_by
+            .get(&(String::from(file), String::from(anchor)))
+            .map(|x| x.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+// names a type can be linked by, e.g. `struct Obj` and `size_t` for
+// `const struct Obj *` and `size_t`. builtin type words are left out.
+pub(crate) fn type_names(ty: &str) -> Vec<String> {
+    const WORDS: [&str; 16] = [
+        "const", "volatile", "restrict", "unsigned", "signed", "long", "short", "int", "char",
+        "void", "float", "double", "_Bool", "bool", "static", "inline",
+    ];
+    let mut names = vec![];
+    let mut keyword: Option<&str> = None;
+    for (_, token) in tokens(ty).into_iter().filter(|(i, _)| *i) {
+        match (keyword.take(), token) {
+            (None, "struct" | "union" | "enum") => keyword = Some(token),
+            (Some(k), x) => names.push(format!("{} {}", k, x)),
+            (None, x) if WORDS.contains(&x) || x.starts_with(|c: char| c.is_ascii_digit()) => {}
+            (None, x) => names.push(String::from(x)),
+        }
+    }
+    names
+}
+
+// first sentence of text on one line, end of sentence is a dot followed by space.
+fn first_sentence(text: &str) -> String {
+    let line = text
+        .lines()
+        .map(|x| x.trim())
+        .take_while(|x| !x.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ");
+    let line = match line.split_once(". ") {
+        Some((x, _)) => format!("{}.", x),
+        None => line,
+    };
+    plain_links(&line)
+}
+
+// intra link shown as plain code, index and deprecated pages have no linker.
+pub(crate) fn plain_links(line: &str) -> String {
+    let mut out = String::new();
+    let mut rest = line;
+    while let Some(open) = rest.find("[`") {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        match after.find("`]") {
+            Some(x) if !after[x + 2..].starts_with(['(', '[']) => {
+                out.push_str(&after[..x + 1]);
+                rest = &after[x + 2..];
+            }
+            _ => {
+                out.push('[');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out.replace('\t', " ")
+}
+
+// create link from one page to symbols of the table.
+#[derive(Clone)]
+pub(crate) struct Linker {
+    table: Arc<SymbolTable>,
+    page: String,
+    file: String,
+    anchor_prefix: Option<String>,
+}
+
+impl Linker {
+    pub(crate) fn new(table: Arc<SymbolTable>, page: &str, file: &str) -> Self {
+        Linker {
+            table,
+            page: String::from(page),
+            file: String::from(file),
+            anchor_prefix: None,
+        }
+    }
+
+    // when many pages share one document, anchor of page items is prefixed so
+    // it does not collide with items of the same name in other page.
+    pub(crate) fn set_anchor_prefix(&mut self, prefix: &str) {
+        self.anchor_prefix = Some(String::from(prefix));
+    }
+
+    // explicit anchor of page item, None when heading text is the anchor.
+    pub(crate) fn anchor(&self, anchor: &str) -> Option<String> {
+        self.anchor_prefix
+            .as_ref()
+            .map(|p| format!("{}{}", p, anchor.trim_start_matches('#')))
+    }
+
+    pub(crate) fn url(&self, symbol: &Symbol) -> String {
+        match symbol.page == self.page {
+            true => format!("#{}", symbol.anchor),
+            false => format!(
+                "{}#{}",
+                relative_url(&self.page, &symbol.page),
+                symbol.anchor
+            ),
+        }
+    }
+
+    // url of type name, None when it is not a documented type or it is the item itself.
+    pub(crate) fn type_url(&self, name: &str, own: &str) -> Option<String> {
+        if name == own {
+            return None;
+        }
+        self.table
+            .get_in(name, &self.file)
+            .filter(|s| s.kind == DocKind::Object && s.name != own)
+            .map(|s| self.url(s))
+    }
+
+    // items using the type of anchor declared in current file, as name, url and
+    // header of item when it is declared in other file.
+    pub(crate) fn used_by(&self, anchor: &str) -> Vec<(String, String, String)> {
+        let anchor = self.anchor(anchor).unwrap_or_else(|| String::from(anchor));
+        self.table
+            .used_by(&self.file, &anchor)
+            .iter()
+            .map(|s| {
+                let file = match s.file == self.file {
+                    true => "",
+                    false => s.file.trim_start_matches(['/', '\\']),
+                };
+                (s.name.clone(), self.url(s), String::from(file))
+            })
+            .collect()
+    }
+
+    // title of documented struct or alias of name, e.g. `struct Obj` for `Obj`.
+    pub(crate) fn object_title(&self, name: &str) -> Option<String> {
+        self.table
+            .get_in(name, &self.file)
+            .filter(|s| s.kind == DocKind::Object)
+            .map(|s| s.name.clone())
+    }
+
+    // url of any symbol, name can be written as `gx_open()`.
+    pub(crate) fn symbol_url(&self, name: &str) -> Option<String> {
+        let name = name.trim_matches('`').trim().trim_end_matches("()");
+        self.table.get_in(name, &self.file).map(|s| self.url(s))
+    }
+
+    // code with its type names linked as html, github does not render link inside fenced code.
+    // None when there is no type to link so caller can keep fenced code.
+    pub(crate) fn link_code(&self, code: &str, own: &str) -> Option<String> {
+        let mut linked = false;
+        let mut out = String::new();
+        for (is_ident, token) in tokens(code) {
+            let url = match is_ident {
+                true => self.type_url(token, own),
+                false => None,
+            };
+            match url {
+                Some(x) => {
+                    linked = true;
+                    out.push_str(&format!("<a href=\"{}\">{}</a>", x, token));
+                }
+                None => out.push_str(&escape_html(token)),
+            }
+        }
+        match linked {
+            true => Some(out),
+            false => None,
+        }
+    }
+
+    // resolve intra-doc link like [`gx_open`] or [`struct Obj`] to page and anchor.
+    // unresolved link written with backtick is reported at line given by line
+    // for the link, plain [text] is left as is.
+    pub(crate) fn link_intra(&self, text: &str, line: &dyn Fn(&str) -> usize) -> String {
+        let mut out = String::new();
+        let mut rest = text;
+        while let Some(open) = rest.find('[') {
+            out.push_str(&rest[..open]);
+            let after = &rest[open + 1..];
+            let close = match after.find(']') {
+                Some(x) => x,
+                None => {
+                    out.push_str(&rest[open..]);
+                    return out;
+                }
+            };
+            let inner = &after[..close];
+            let next = &after[close + 1..];
+            rest = next;
+
+            // already a markdown link or reference
+            if next.starts_with('(') || next.starts_with('[') || inner.contains('[') {
+                out.push_str(&format!("[{}]", inner));
+                continue;
+            }
+
+            let quoted = inner.len() > 1 && inner.starts_with('`') && inner.ends_with('`');
+            match self.symbol_url(inner) {
+                Some(x) => out.push_str(&format!("[{}]({})", inner, x)),
+                None => {
+                    if quoted {
+                        eprintln!(
+                            "::> Warning: unresolved link [{}] in {}:{}.",
+                            inner,
+                            self.file,
+                            line(&format!("[{}]", inner))
+                        );
+                    }
+                    out.push_str(&format!("[{}]", inner));
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+
+    // link type names in description, text inside link or code span is kept as is.
+    pub(crate) fn link_text(&self, text: &str, own: &str) -> String {
+        let mut out = String::new();
+        let mut in_code = false;
+        let mut in_link = 0;
+        for (is_ident, token) in tokens(text) {
+            if !is_ident {
+                for c in token.chars() {
+                    match c {
+                        '`' => in_code = !in_code,
+                        '[' | '(' if !in_code => in_link += 1,
+                        ']' | ')' if !in_code && in_link > 0 => in_link -= 1,
+                        _ => {}
+                    }
+                }
+                out.push_str(token);
+                continue;
+            }
+            match (in_code || in_link > 0, self.type_url(token, own)) {
+                (false, Some(x)) => out.push_str(&format!("[{}]({})", token, x)),
+                _ => out.push_str(token),
+            }
+        }
+        out
+    }
+}
+
+// path of page `to` seen from page `from`, both relative to root of output.
+// wiki page name has no directory so it is kept as is.
+pub(crate) fn relative_url(from: &str, to: &str) -> String {
+    let from = from.split('/').collect::<Vec<&str>>();
+    let to = to.split('/').collect::<Vec<&str>>();
+    let from_dir = &from[..from.len() - 1];
+    let to_dir = &to[..to.len() - 1];
+    let common = from_dir
+        .iter()
+        .zip(to_dir)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut parts = vec![".."; from_dir.len() - common];
+    parts.extend(&to[common..]);
+    parts.join("/")
+}
+
+// split text into identifier and non identifier parts.
+pub(crate) fn tokens(text: &str) -> Vec<(bool, &str)> {
+    let mut tokens = vec![];
+    let mut start = 0;
+    let mut is_ident: Option<bool> = None;
+    for (i, c) in text.char_indices() {
+        let ident = c.is_ascii_alphanumeric() || c == '_';
+        match is_ident {
+            Some(x) if x == ident => {}
+            Some(x) => {
+                tokens.push((x, &text[start..i]));
+                start = i;
+                is_ident = Some(ident);
+            }
+            None => is_ident = Some(ident),
+        }
+    }
+    if let Some(x) = is_ident {
+        tokens.push((x, &text[start..]));
+    }
+    tokens
+}
+
+pub(crate) fn escape_html(str: &str) -> String {
+    str.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(name: &str, kind: DocKind, page: &str, uses: &[&str]) -> Symbol {
+        Symbol {
+            name: String::from(name),
+            kind,
+            page: String::from(page),
+            anchor: name.to_lowercase().replace(' ', "-"),
+            file: format!("/{}.h", page.to_lowercase()),
+            summary: String::new(),
+            uses: uses.iter().map(|x| x.to_string()).collect(),
+            deprecated: None,
+        }
+    }
+
+    fn object(name: &str, page: &str) -> (Vec<String>, Symbol) {
+        let names = vec![format!("struct {}", name), String::from(name)];
+        (
+            names,
+            symbol(&format!("struct {}", name), DocKind::Object, page, &[]),
+        )
+    }
+
+    fn function(name: &str, page: &str, uses: &[&str]) -> (Vec<String>, Symbol) {
+        (
+            vec![String::from(name)],
+            symbol(name, DocKind::Function, page, uses),
+        )
+    }
+
+    fn table() -> Arc<SymbolTable> {
+        Arc::new(SymbolTable::from_symbols(&[
+            vec![object("Obj", "T"), function("t_only", "T", &[])],
+            vec![object("Obj", "Home"), function("use_obj", "Home", &["Obj"])],
+            vec![function("other", "Other", &["Obj"])],
+        ]))
+    }
+
+    #[test]
+    fn own_definition_first() {
+        let home = Linker::new(table(), "Home", "/home.h");
+        assert_eq!(home.type_url("Obj", "").as_deref(), Some("#struct-obj"));
+        assert_eq!(
+            home.symbol_url("struct Obj").as_deref(),
+            Some("#struct-obj")
+        );
+        assert_eq!(home.symbol_url("t_only()").as_deref(), Some("T#t_only"));
+
+        let t = Linker::new(table(), "T", "/t.h");
+        assert_eq!(t.type_url("Obj", "").as_deref(), Some("#struct-obj"));
+    }
+
+    #[test]
+    fn first_definition_outside() {
+        let other = Linker::new(table(), "Other", "/other.h");
+        assert_eq!(other.type_url("Obj", "").as_deref(), Some("T#struct-obj"));
+        assert_eq!(other.object_title("Obj").as_deref(), Some("struct Obj"));
+        assert_eq!(other.type_url("Obj", "struct Obj"), None);
+        assert_eq!(other.symbol_url("missing"), None);
+    }
+
+    #[test]
+    fn used_by_own_definition() {
+        let home = Linker::new(table(), "Home", "/home.h");
+        let used = home.used_by("struct-obj");
+        assert_eq!(used.len(), 1);
+        assert_eq!(used[0].0, "use_obj");
+        assert_eq!(used[0].1, "#use_obj");
+        assert_eq!(used[0].2, "");
+
+        let t = Linker::new(table(), "T", "/t.h");
+        let used = t.used_by("struct-obj");
+        assert_eq!(used.len(), 1);
+        assert_eq!(
+            used[0],
+            ("other".into(), "Other#other".into(), "other.h".into())
+        );
+    }
+
+    #[test]
+    fn type_names_of_declaration() {
+        assert_eq!(type_names("const struct Obj *"), ["struct Obj"]);
+        assert_eq!(type_names("Obj *"), ["Obj"]);
+        assert!(type_names("unsigned int").is_empty());
+    }
+
+    #[test]
+    fn intra_links_resolved() {
+        let home = Linker::new(table(), "Home", "/home.h");
+        let no_line = |_: &str| 0;
+        assert_eq!(
+            home.link_intra("see [`t_only()`] and [`struct Obj`].", &no_line),
+            "see [`t_only()`](T#t_only) and [`struct Obj`](#struct-obj)."
+        );
+        // link and reference already written are kept
+        assert_eq!(
+            home.link_intra("[`t_only`](x.md) [`t_only`][r] [a [`b`]", &no_line),
+            "[`t_only`](x.md) [`t_only`][r] [a [`b`]"
+        );
+    }
+
+    #[test]
+    fn intra_links_unresolved() {
+        let home = Linker::new(table(), "Home", "/home.h");
+        let asked = std::cell::RefCell::new(vec![]);
+        let line = |x: &str| {
+            asked.borrow_mut().push(x.to_string());
+            7
+        };
+        assert_eq!(
+            home.link_intra("[`nope`] and [plain] and [`use_obj`", &line),
+            "[`nope`] and [plain] and [`use_obj`"
+        );
+        // only link written with backtick is reported, at line of the link
+        assert_eq!(*asked.borrow(), vec!["[`nope`]"]);
+    }
+
+    #[test]
+    fn text_links() {
+        let home = Linker::new(table(), "Home", "/home.h");
+        assert_eq!(
+            home.link_text("take Obj, not `Obj` nor [Obj](x) or Objs", ""),
+            "take [Obj](#struct-obj), not `Obj` nor [Obj](x) or Objs"
+        );
+        // item does not link to itself
+        assert_eq!(home.link_text("an Obj", "struct Obj"), "an Obj");
+        let other = Linker::new(table(), "Other", "/other.h");
+        assert_eq!(other.link_text("Obj", ""), "[Obj](T#struct-obj)");
+        assert_eq!(other.link_text("t_only", ""), "t_only");
+    }
+}
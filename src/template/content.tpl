@@ -0,0 +1,12 @@
+{{main}}{{#each sections}}
+### **{{title}}{{#unless group}}:{{/unless}}**
+{{#if group}}{{#if intro}}
+{{intro}}
+{{/if}}
+{{else}}
+{{#if internal}}> **Internal:** not part of public API, may change without notice.
+
+{{/if}}{{/if}}---
+
+{{#each items}}{{.}}{{#unless @last}}
+{{/unless}}{{/each}}{{/each}}
@@ -0,0 +1,25 @@
+{{#if include}}* {{code}}{{#if desc}}\
+	{{desc}}{{/if}}{{else}}* #### {{#if anchor}}<a id="{{anchor}}"></a>{{/if}}**{{title}}**
+{{#if show_code}}{{#if linked_code}}	<pre>{{linked_code}}</pre>
+{{else}}	```c
+{{code}}
+	```
+{{/if}}{{/if}}{{#if deprecated}}
+
+	> ⚠️ **Deprecated**{{#if deprecated_reason}}: {{deprecated_reason}}{{/if}}{{/if}}{{#if desc}}
+
+	{{desc}}{{/if}}{{#if fields}}
+
+	| Field | Type | Array/Bits | Description |
+	| --- | --- | --- | --- |{{#each fields}}
+	| `{{name}}` | {{type}} | {{extent}} | {{desc}} |{{/each}}{{/if}}{{#if params}}
+
+	| Parameter | Type | Direction | Description |
+	| --- | --- | --- | --- |{{#each params}}
+	| {{#if name}}`{{name}}`{{/if}} | {{type}} | {{direction}} | {{desc}} |{{/each}}{{/if}}{{#if returns}}
+
+	**Returns** {{returns.type}}{{#if returns.desc}}: {{returns.desc}}{{/if}}{{/if}}{{#each tags}}
+
+	**{{title}}** {{text}}{{/each}}{{#if used_by}}
+
+	**Used by** {{#each used_by}}[`{{name}}`]({{url}}){{#if file}} *({{file}})*{{/if}}{{#unless @last}}, {{/unless}}{{/each}}{{/if}}{{/if}}
@@ -0,0 +1,453 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashSet},
+    fs::read_to_string,
+    path::Path,
+    sync::{Arc, OnceLock},
+};
+
+use crate::{cache::hash, file_reader::file_list, json::Json};
+
+// extension of template file in template directory.
+const EXT: &str = ".tpl";
+
+// layout of markdown pages, each can be replaced by `<name>.tpl` of template directory.
+const DEFAULTS: [(&str, &str); 4] = [
+    ("page", include_str!("page.tpl")),
+    ("content", include_str!("content.tpl")),
+    ("item", include_str!("item.tpl")),
+    ("sidebar", include_str!("sidebar.tpl")),
+];
+
+// `{{name}}` value, `{{#each name}}` loop, `{{#if name}}` and `{{#unless name}}`
+// condition with optional `{{else}}`, `{{> name}}` include of other template.
+enum Node {
+    Text(String),
+    Var(String),
+    Each(String, Vec<Node>),
+    If(String, Vec<Node>, Vec<Node>),
+    Include(String),
+}
+
+// block opened by `{{#tag name}}` and not closed yet.
+struct Block {
+    tag: String,
+    name: String,
+    body: Vec<Node>,
+    other: Option<Vec<Node>>,
+    line: usize,
+}
+
+impl Block {
+    fn new(tag: &str, name: &str, line: usize) -> Self {
+        Block {
+            tag: String::from(tag),
+            name: String::from(name),
+            body: vec![],
+            other: None,
+            line,
+        }
+    }
+
+    fn push(&mut self, node: Node) {
+        match self.other.as_mut() {
+            Some(x) => x.push(node),
+            None => self.body.push(node),
+        }
+    }
+}
+
+fn parse(src: &str) -> Result<Vec<Node>, String> {
+    let mut stack = vec![Block::new("", "", 0)];
+    let mut rest = src;
+    let mut line = 1;
+    while let Some(open) = rest.find("{{") {
+        let text = &rest[..open];
+        if !text.is_empty() {
+            stack
+                .last_mut()
+                .unwrap()
+                .push(Node::Text(String::from(text)));
+        }
+        line += text.matches('\n').count();
+        let after = &rest[open + 2..];
+        let close = match after.find("}}") {
+            Some(x) => x,
+            None => return Err(format!("line {}: unclosed tag", line)),
+        };
+        let tag = after[..close].trim();
+        rest = &after[close + 2..];
+
+        if let Some(x) = tag.strip_prefix('#') {
+            let (kind, name) = x.split_once(' ').unwrap_or((x, ""));
+            let name = name.trim();
+            if !["each", "if", "unless"].contains(&kind) {
+                return Err(format!("line {}: unknown block {}", line, kind));
+            }
+            if name.is_empty() {
+                return Err(format!("line {}: block {} without value", line, kind));
+            }
+            stack.push(Block::new(kind, name, line));
+        } else if let Some(x) = tag.strip_prefix('/') {
+            let block = stack.pop().unwrap();
+            if stack.is_empty() || block.tag != x.trim() {
+                return Err(format!("line {}: unexpected close of {}", line, x.trim()));
+            }
+            let node = match block.tag.as_str() {
+                "each" if block.other.is_some() => {
+                    return Err(format!("line {}: else in each block", block.line))
+                }
+                "each" => Node::Each(block.name, block.body),
+                "if" => Node::If(block.name, block.body, block.other.unwrap_or_default()),
+                _ => Node::If(block.name, block.other.unwrap_or_default(), block.body),
+            };
+            stack.last_mut().unwrap().push(node);
+        } else if tag == "else" {
+            let block = stack.last_mut().unwrap();
+            if !["if", "unless"].contains(&block.tag.as_str()) || block.other.is_some() {
+                return Err(format!("line {}: unexpected else", line));
+            }
+            block.other = Some(vec![]);
+        } else if let Some(x) = tag.strip_prefix('>') {
+            stack
+                .last_mut()
+                .unwrap()
+                .push(Node::Include(String::from(x.trim())));
+        } else if !tag.starts_with('!') {
+            if tag.is_empty() {
+                return Err(format!("line {}: empty tag", line));
+            }
+            stack.last_mut().unwrap().push(Node::Var(String::from(tag)));
+        }
+        line += after[..close].matches('\n').count();
+    }
+    if !rest.is_empty() {
+        stack
+            .last_mut()
+            .unwrap()
+            .push(Node::Text(String::from(rest)));
+    }
+
+    let root = stack.remove(0);
+    match stack.pop() {
+        Some(x) => Err(format!("line {}: unclosed block {}", x.line, x.tag)),
+        None => Ok(root.body),
+    }
+}
+
+// included template names, to find missing template and include cycle.
+fn includes<'a>(nodes: &'a [Node], out: &mut Vec<&'a str>) {
+    for node in nodes {
+        match node {
+            Node::Include(x) => out.push(x),
+            Node::Each(_, body) => includes(body, out),
+            Node::If(_, then, other) => {
+                includes(then, out);
+                includes(other, out);
+            }
+            Node::Text(_) | Node::Var(_) => {}
+        }
+    }
+}
+
+// value in scope of rendering, loop item keep its position.
+#[derive(Clone, Copy)]
+struct Frame<'a> {
+    value: &'a Json,
+    index: Option<(usize, usize)>,
+}
+
+fn is_truthy(value: &Json) -> bool {
+    match value {
+        Json::Null => false,
+        Json::Bool(x) => *x,
+        Json::Num(x) => *x != 0.0,
+        Json::Str(x) => !x.is_empty(),
+        Json::Arr(x) => !x.is_empty(),
+        Json::Obj(_) => true,
+    }
+}
+
+fn display(value: &Json) -> String {
+    match value {
+        Json::Str(x) => x.clone(),
+        Json::Num(x) if x.fract() == 0.0 && x.is_finite() => format!("{}", *x as i64),
+        Json::Num(x) => format!("{}", x),
+        Json::Bool(x) => format!("{}", x),
+        Json::Null | Json::Arr(_) | Json::Obj(_) => String::new(),
+    }
+}
+
+// `.` is current value, `@index`, `@first` and `@last` are position in innermost loop,
+// dotted name is searched from innermost to outermost scope.
+fn lookup<'a>(frames: &[Frame<'a>], name: &str) -> Option<Cow<'a, Json>> {
+    if name == "." {
+        return frames.last().map(|f| Cow::Borrowed(f.value));
+    }
+    if let Some(x) = name.strip_prefix('@') {
+        let (i, len) = frames.iter().rev().find_map(|f| f.index)?;
+        return match x {
+            "index" => Some(Cow::Owned(Json::Num(i as f64))),
+            "first" => Some(Cow::Owned(Json::Bool(i == 0))),
+            "last" => Some(Cow::Owned(Json::Bool(i + 1 == len))),
+            _ => None,
+        };
+    }
+    let mut path = name.split('.');
+    let first = path.next()?;
+    let value = frames.iter().rev().find_map(|f| f.value.get(first))?;
+    path.try_fold(value, |v, p| v.get(p)).map(Cow::Borrowed)
+}
+
+pub(crate) struct Templates {
+    nodes: BTreeMap<String, Vec<Node>>,
+    hash: u64,
+}
+
+impl Templates {
+    // built-in templates, shared by every page not given other templates.
+    pub(crate) fn shared() -> Arc<Templates> {
+        static DEFAULT: OnceLock<Arc<Templates>> = OnceLock::new();
+        DEFAULT
+            .get_or_init(|| Arc::new(Templates::load(None).unwrap()))
+            .clone()
+    }
+
+    // built-in templates replaced by every `.tpl` file of dir, other files are
+    // available as include.
+    pub(crate) fn load(dir: Option<&str>) -> Result<Self, String> {
+        let mut sources = DEFAULTS
+            .iter()
+            .map(|(n, s)| (String::from(*n), String::from(*s)))
+            .collect::<BTreeMap<String, String>>();
+        if let Some(dir) = dir {
+            if !Path::new(dir).is_dir() {
+                return Err(format!("template directory {} not found", dir));
+            }
+            let mut files = vec![];
+            file_list(&mut files, dir, EXT).map_err(|e| format!("{}: {}", dir, e))?;
+            for f in files {
+                let name = Path::new(&f)
+                    .file_stem()
+                    .map(|x| x.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let src = read_to_string(&f).map_err(|e| format!("{}: {}", f, e))?;
+                sources.insert(name, src);
+            }
+        }
+
+        let mut nodes = BTreeMap::new();
+        let mut key = String::new();
+        for (name, src) in &sources {
+            // file end with newline that is not part of layout
+            let src = src.strip_suffix('\n').unwrap_or(src);
+            let src = src.strip_suffix('\r').unwrap_or(src);
+            let parsed = parse(src).map_err(|e| format!("template {}: {}", name, e))?;
+            nodes.insert(name.clone(), parsed);
+            key.push_str(&format!("{}\0{}\0", name, src));
+        }
+
+        let templates = Templates {
+            nodes,
+            hash: hash(key.as_bytes()),
+        };
+        for name in templates.nodes.keys() {
+            templates.check_includes(name, &mut vec![])?;
+        }
+        Ok(templates)
+    }
+
+    fn check_includes<'a>(&'a self, name: &'a str, path: &mut Vec<&'a str>) -> Result<(), String> {
+        if path.contains(&name) {
+            return Err(format!(
+                "template {} include itself through {}",
+                name,
+                path.join(" > ")
+            ));
+        }
+        let nodes = match self.nodes.get(name) {
+            Some(x) => x,
+            None => {
+                return Err(format!(
+                    "template {} include missing template {}",
+                    path.last().unwrap_or(&""),
+                    name
+                ))
+            }
+        };
+        let mut names = vec![];
+        includes(nodes, &mut names);
+        let names = names.into_iter().collect::<HashSet<&str>>();
+        path.push(name);
+        for x in names {
+            self.check_includes(x, path)?;
+        }
+        path.pop();
+        Ok(())
+    }
+
+    // hash of every template source, part of cache key.
+    pub(crate) fn get_hash(&self) -> u64 {
+        self.hash
+    }
+
+    pub(crate) fn render(&self, name: &str, context: &Json) -> String {
+        let mut out = String::new();
+        let frames = vec![Frame {
+            value: context,
+            index: None,
+        }];
+        self.render_into(&mut out, name, &frames);
+        out
+    }
+
+    fn render_into(&self, out: &mut String, name: &str, frames: &[Frame]) {
+        if let Some(x) = self.nodes.get(name) {
+            self.render_nodes(out, x, frames);
+        }
+    }
+
+    fn render_nodes(&self, out: &mut String, nodes: &[Node], frames: &[Frame]) {
+        for node in nodes {
+            match node {
+                Node::Text(x) => out.push_str(x),
+                Node::Var(x) => {
+                    if let Some(v) = lookup(frames, x) {
+                        out.push_str(&display(&v));
+                    }
+                }
+                Node::Each(x, body) => {
+                    let value = lookup(frames, x);
+                    let list = match value.as_deref() {
+                        Some(Json::Arr(list)) => list,
+                        _ => continue,
+                    };
+                    for (i, item) in list.iter().enumerate() {
+                        let mut inner = frames.to_vec();
+                        inner.push(Frame {
+                            value: item,
+                            index: Some((i, list.len())),
+                        });
+                        self.render_nodes(out, body, &inner);
+                    }
+                }
+                Node::If(x, then, other) => {
+                    match lookup(frames, x).map(|v| is_truthy(&v)).unwrap_or(false) {
+                        true => self.render_nodes(out, then, frames),
+                        false => self.render_nodes(out, other, frames),
+                    }
+                }
+                Node::Include(x) => self.render_into(out, x, frames),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn templates(sources: &[(&str, &str)]) -> Result<Templates, String> {
+        let mut nodes = BTreeMap::new();
+        for (name, src) in sources {
+            nodes.insert(String::from(*name), parse(src)?);
+        }
+        let templates = Templates { nodes, hash: 0 };
+        for name in templates.nodes.keys() {
+            templates.check_includes(name, &mut vec![])?;
+        }
+        Ok(templates)
+    }
+
+    fn render(src: &str, context: &Json) -> String {
+        templates(&[("t", src)]).unwrap().render("t", context)
+    }
+
+    fn context() -> Json {
+        Json::obj(vec![
+            ("title", Json::str("Obj")),
+            ("count", Json::Num(2.0)),
+            ("empty", Json::str("")),
+            ("item", Json::obj(vec![("name", Json::str("a"))])),
+            (
+                "list",
+                Json::Arr(vec![
+                    Json::obj(vec![("name", Json::str("x")), ("tags", Json::Arr(vec![]))]),
+                    Json::obj(vec![
+                        ("name", Json::str("y")),
+                        ("tags", Json::Arr(vec![Json::str("t1"), Json::str("t2")])),
+                    ]),
+                ]),
+            ),
+        ])
+    }
+
+    #[test]
+    fn variables() {
+        let cx = context();
+        assert_eq!(render("# {{title}} ({{count}})", &cx), "# Obj (2)");
+        assert_eq!(
+            render("{{item.name}}|{{missing}}|{{item.missing}}", &cx),
+            "a||"
+        );
+        assert_eq!(render("a{{! comment }}b", &cx), "ab");
+    }
+
+    #[test]
+    fn conditions() {
+        let cx = context();
+        assert_eq!(render("{{#if title}}yes{{else}}no{{/if}}", &cx), "yes");
+        assert_eq!(render("{{#if empty}}yes{{else}}no{{/if}}", &cx), "no");
+        assert_eq!(render("{{#unless empty}}yes{{/unless}}", &cx), "yes");
+        assert_eq!(render("{{#if missing}}yes{{/if}}", &cx), "");
+    }
+
+    #[test]
+    fn nested_loops() {
+        let src = "{{#each list}}{{name}}{{#if tags}}[{{#each tags}}{{.}}{{#unless @last}},{{/unless}}{{/each}}]{{/if}}{{#unless @last}} {{/unless}}{{/each}}";
+        assert_eq!(render(src, &context()), "x y[t1,t2]");
+        // outer scope is visible in loop
+        let src = "{{#each list}}{{@index}}:{{title}}{{#if @first}}!{{/if}};{{/each}}";
+        assert_eq!(render(src, &context()), "0:Obj!;1:Obj;");
+    }
+
+    #[test]
+    fn include() {
+        let t = templates(&[("t", "<{{> part}}>"), ("part", "{{title}}")]).unwrap();
+        assert_eq!(t.render("t", &context()), "<Obj>");
+        assert!(templates(&[("t", "{{> gone}}")]).is_err());
+        assert!(templates(&[("t", "{{> u}}"), ("u", "{{> t}}")]).is_err());
+    }
+
+    #[test]
+    fn values_are_not_escaped() {
+        // output is markdown, html and pipe are escaped by caller where needed
+        let cx = Json::obj(vec![("code", Json::str("<a href=\"x\">a|b</a> & {{y}}"))]);
+        assert_eq!(render("{{code}}", &cx), "<a href=\"x\">a|b</a> & {{y}}");
+    }
+
+    #[test]
+    fn errors() {
+        let err = |src: &str| parse(src).err().unwrap();
+        assert_eq!(err("a\n{{#if x}}b"), "line 2: unclosed block if");
+        assert_eq!(err("{{#each x}}{{/if}}"), "line 1: unexpected close of if");
+        assert_eq!(err("{{/if}}"), "line 1: unexpected close of if");
+        assert_eq!(err("{{title"), "line 1: unclosed tag");
+        assert_eq!(err("{{#with x}}{{/with}}"), "line 1: unknown block with");
+        assert_eq!(err("{{#if}}{{/if}}"), "line 1: block if without value");
+        assert_eq!(
+            err("{{#each x}}{{else}}{{/each}}"),
+            "line 1: unexpected else"
+        );
+        assert_eq!(
+            err("{{#if x}}{{else}}{{else}}{{/if}}"),
+            "line 1: unexpected else"
+        );
+        assert_eq!(err("{{ }}"), "line 1: empty tag");
+    }
+
+    #[test]
+    fn default_templates_parse() {
+        assert!(Templates::load(None).is_ok());
+    }
+}
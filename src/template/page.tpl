@@ -0,0 +1,18 @@
+# {{title}}
+*{{path}}*
+
+---
+{{#if toc}}
+{{sidebar}}
+
+---
+{{/if}}{{#if has_content}}
+{{> content}}{{/if}}{{#if class_diagram}}
+
+### **Type diagram**
+
+{{class_diagram}}{{/if}}{{#if include_graph}}
+
+### **Include graph**
+
+{{include_graph}}{{/if}}
@@ -0,0 +1,4 @@
+{{#each sections}}#### **{{title}} ({{count}})**
+{{#each links}}{{#if nested}}  {{/if}}{{#if group}}* **{{name}}**{{else}}{{#if url}}* {{#if deprecated}}~~{{/if}}[{{name}}]({{url}}){{#if deprecated}}~~{{/if}}{{else}}* {{name}}{{/if}}{{/if}}{{#unless @last}}
+{{/unless}}{{/each}}{{#unless @last}}
+{{/unless}}{{/each}}
@@ -1,35 +1,66 @@
-use std::cell::RefCell;
-
-use crate::page::view::link::Link;
+use crate::{page::view::link::Link, slug::slug};
 
 use super::{c_function_param::CFunctionParams, AnchorMd, IntoMd, TitleMd};
 
 #[derive(Clone, PartialEq, Eq)]
 pub(crate) struct CFunction {
-    unit: RefCell<String>,
-    name: RefCell<String>,
-    parameters: RefCell<Vec<CFunctionParams>>,
+    unit: String,
+    name: String,
+    parameters: Vec<CFunctionParams>,
 }
 
 impl CFunction {
     pub(crate) fn new() -> Self {
         CFunction {
-            name: RefCell::new(String::new()),
-            unit: RefCell::new(String::new()),
-            parameters: RefCell::new(vec![]),
+            name: String::new(),
+            unit: String::new(),
+            parameters: vec![],
         }
     }
 
-    pub(crate) fn set_unit(&self, str: &str) {
-        *self.unit.borrow_mut() = String::from(str);
+    pub(crate) fn set_unit(&mut self, str: &str) {
+        self.unit = String::from(str);
+    }
+
+    pub(crate) fn set_name(&mut self, str: &str) {
+        self.name = String::from(str);
+    }
+
+    pub(crate) fn add_param(&mut self, param: CFunctionParams) {
+        self.parameters.push(param);
+    }
+
+    pub(crate) fn get_name(&self) -> String {
+        self.name.to_owned()
+    }
+
+    // return type with pointer mark that parsed as part of name.
+    pub(crate) fn get_return(&self) -> String {
+        let name = &self.name;
+        let ptr = name.chars().take_while(|c| *c == '*').collect::<String>();
+        format!("{}{}", self.unit, ptr)
+    }
+
+    pub(crate) fn get_params(&self) -> Vec<CFunctionParams> {
+        self.parameters.clone()
     }
 
-    pub(crate) fn set_name(&self, str: &str) {
-        *self.name.borrow_mut() = String::from(str);
+    // function return nothing when unit is void without pointer.
+    pub(crate) fn is_void(&self) -> bool {
+        self.unit == "void" && !self.name.starts_with('*')
     }
 
-    pub(crate) fn add_param(&self, param: CFunctionParams) {
-        self.parameters.borrow_mut().push(param);
+    // compare return type and parameter types, parameter names are ignored.
+    pub(crate) fn same_signature(&self, other: &CFunction) -> bool {
+        let params = &self.parameters;
+        let other_params = &other.parameters;
+        self.unit == *other.unit
+            && self.name == *other.name
+            && params.len() == other_params.len()
+            && params
+                .iter()
+                .zip(other_params.iter())
+                .all(|(a, b)| a.signature() == b.signature())
     }
 }
 
@@ -37,31 +68,25 @@ impl IntoMd for CFunction {
     fn into_md(&self) -> String {
         let params = self
             .parameters
-            .borrow()
             .iter()
             .map(|x| x.into_md())
             .collect::<Vec<String>>();
         let params = params.join(", ");
 
-        format!(
-            "\t{} {}({});",
-            self.unit.borrow(),
-            self.name.borrow(),
-            params
-        )
+        format!("\t{} {}({});", self.unit, self.name, params)
     }
 }
 
 impl TitleMd for CFunction {
     fn create_title(&self) -> String {
-        self.name.borrow().to_owned()
+        self.name.to_owned()
     }
 }
 
 impl AnchorMd for CFunction {
     fn create_anchor(&self) -> Option<crate::page::view::link::Link> {
         let title = self.create_title();
-        let url = format!("#{}", title.to_lowercase().replace(" ", "-"));
+        let url = format!("#{}", slug(&title));
         Some(Link::new(&title, &url, false))
     }
 }
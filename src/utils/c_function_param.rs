@@ -1,30 +1,55 @@
-use std::cell::RefCell;
-
 use super::IntoMd;
 
 #[derive(Clone, PartialEq, Eq)]
 pub(crate) struct CFunctionParams {
-    unit: RefCell<String>,
-    name: RefCell<Option<String>>,
+    unit: String,
+    name: Option<String>,
 }
 
 impl CFunctionParams {
     pub(crate) fn new(unit: &str, name: Option<String>) -> Self {
         CFunctionParams {
-            unit: RefCell::new(String::from(unit)),
-            name: RefCell::new(name),
+            unit: String::from(unit),
+            name,
         }
     }
+
+    pub(crate) fn get_unit(&self) -> String {
+        self.unit.to_owned()
+    }
+
+    // name without pointer mark and array.
+    pub(crate) fn get_name(&self) -> Option<String> {
+        let name = &self.name;
+        name.as_ref()
+            .map(|x| x.trim_start_matches('*'))
+            .map(|x| x.split('[').next().unwrap_or_default().to_string())
+            .filter(|x| !x.is_empty())
+    }
+
+    // type of parameter without its name, pointer mark and array on name are kept.
+    pub(crate) fn signature(&self) -> String {
+        let name = &self.name;
+        let (ptr, array) = match name.as_ref() {
+            Some(x) => (
+                x.chars().take_while(|c| *c == '*').collect::<String>(),
+                x.find('[').map(|i| &x[i..]).unwrap_or_default(),
+            ),
+            None => (String::new(), ""),
+        };
+        format!("{}{}{}", self.unit, ptr, array)
+    }
 }
 
 impl IntoMd for CFunctionParams {
     fn into_md(&self) -> String {
-        let name = self.name.borrow();
-        let name = match name.as_ref() {
-            Some(x) => format!(" {}", x),
-            None => String::new(),
-        };
-
-        format!("{}{}", self.unit.borrow(), name)
+        let name = &self.name;
+        match name.as_ref() {
+            Some(x) if self.unit.contains("(*)") => {
+                self.unit.replacen("(*)", &format!("(*{})", x), 1)
+            }
+            Some(x) => format!("{} {}", self.unit, x),
+            None => self.unit.to_owned(),
+        }
     }
 }
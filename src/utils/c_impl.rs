@@ -0,0 +1,172 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+};
+
+use super::{
+    c_function::CFunction,
+    doc_tag::{parse_tags, DocTag},
+};
+
+// documentation of function definition found in .c file.
+#[derive(Clone)]
+pub(crate) struct CImplDoc {
+    func: CFunction,
+    desc: String,
+    file: String,
+}
+
+impl CImplDoc {
+    pub(crate) fn new(func: CFunction, desc: &str, file: &str) -> Self {
+        CImplDoc {
+            func,
+            desc: String::from(desc),
+            file: String::from(file),
+        }
+    }
+}
+
+// all documented function definitions, indexed by function name.
+pub(crate) struct CImplDocs {
+    funcs: HashMap<String, Vec<CImplDoc>>,
+    // file and name of definitions already warned about, headers are parsed in parallel.
+    warned: Mutex<HashSet<(String, String)>>,
+}
+
+impl CImplDocs {
+    pub(crate) fn new() -> Self {
+        CImplDocs {
+            funcs: HashMap::new(),
+            warned: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub(crate) fn add(&mut self, doc: CImplDoc) {
+        self.funcs.entry(doc.func.get_name()).or_default().push(doc);
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.funcs.values().map(|x| x.len()).sum()
+    }
+
+    // merge description of header prototype with description of its definition.
+    pub(crate) fn merge(&self, func: &CFunction, desc: Option<String>) -> Option<String> {
+        let funcs = &self.funcs;
+        let found = match funcs.get(&func.get_name()) {
+            Some(x) => x.iter().find(|d| d.func.same_signature(func)),
+            None => None,
+        };
+
+        let found = match found {
+            Some(x) => x,
+            None => return desc,
+        };
+
+        match desc {
+            Some(x) if x == found.desc => Some(x),
+            Some(x) => {
+                let key = (found.file.clone(), func.get_name());
+                let first = match self.warned.lock() {
+                    Ok(mut warned) => warned.insert(key),
+                    Err(_) => true,
+                };
+                if first {
+                    eprintln!(
+                        "::> Warning: documentation of {} differs from its definition in {}.",
+                        func.get_name(),
+                        found.file
+                    );
+                }
+                Some(merge_desc(&x, &found.desc))
+            }
+            None => Some(found.desc.clone()),
+        }
+    }
+}
+
+// what a tag documents, the same key in both descriptions is kept once.
+fn tag_key(tag: &DocTag) -> (String, String) {
+    match tag.get_tag() {
+        "param" => (
+            String::from("param"),
+            String::from(tag.get_arg().unwrap_or_default()),
+        ),
+        "return" | "returns" => (String::from("return"), String::new()),
+        x => (String::from(x), String::from(tag.get_text())),
+    }
+}
+
+// text of both descriptions, then tags of header with tags of definition it lacks.
+fn merge_desc(header: &str, definition: &str) -> String {
+    let (text, mut tags) = parse_tags(header);
+    let (impl_text, impl_tags) = parse_tags(definition);
+    let mut parts = vec![text];
+    if !parts[0].contains(&impl_text) {
+        parts.push(impl_text);
+    }
+    let keys = tags.iter().map(tag_key).collect::<Vec<_>>();
+    tags.extend(
+        impl_tags
+            .into_iter()
+            .filter(|t| !keys.contains(&tag_key(t))),
+    );
+    parts.extend(tags.iter().map(|t| t.to_desc()));
+    parts
+        .into_iter()
+        .filter(|x| !x.is_empty())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_function;
+
+    fn docs(desc: &str) -> CImplDocs {
+        let mut docs = CImplDocs::new();
+        let func = parse_function("int open(int fd, int flags);");
+        docs.add(CImplDoc::new(func, desc, "/a.c"));
+        docs
+    }
+
+    #[test]
+    fn merged_tag_by_tag() {
+        let docs =
+            docs("open a file. @param fd file @param flags mode @return status @see close()");
+        let func = parse_function("int open(int fd, int flags);");
+        let desc = docs.merge(
+            &func,
+            Some(String::from("open it. @param fd descriptor @returns fd")),
+        );
+        assert_eq!(
+            desc.as_deref(),
+            Some("open it. open a file. @param fd descriptor @returns fd @param flags mode @see close()")
+        );
+    }
+
+    #[test]
+    fn same_text_kept_once() {
+        let docs = docs("open it. @param fd file");
+        let func = parse_function("int open(int fd, int flags);");
+        let desc = docs.merge(&func, Some(String::from("open it. @param flags mode")));
+        assert_eq!(
+            desc.as_deref(),
+            Some("open it. @param flags mode @param fd file")
+        );
+        assert_eq!(
+            docs.merge(&func, None).as_deref(),
+            Some("open it. @param fd file")
+        );
+    }
+
+    #[test]
+    fn warned_once_per_definition() {
+        let docs = docs("open it.");
+        let func = parse_function("int open(int fd, int flags);");
+        docs.merge(&func, Some(String::from("open a file.")));
+        docs.merge(&func, Some(String::from("open other.")));
+        let warned = docs.warned.lock().map(|x| x.len()).unwrap_or_default();
+        assert_eq!(warned, 1);
+    }
+}
@@ -1,48 +1,57 @@
-use std::cell::RefCell;
-
 use crate::page::view::link::Link;
 
 use super::{AnchorMd, IntoMd, TitleMd};
 
 #[derive(Clone)]
 pub(crate) struct CIncludes {
-    name: RefCell<String>,
-    url: RefCell<String>,
+    name: String,
+    url: String,
 }
 
 impl CIncludes {
     pub(crate) fn new() -> Self {
         CIncludes {
-            name: RefCell::new(String::new()),
-            url: RefCell::new(String::new()),
+            name: String::new(),
+            url: String::new(),
         }
     }
 
-    pub(crate) fn set_name(&self, str: &str) {
-        *self.name.borrow_mut() = String::from(str);
+    pub(crate) fn set_name(&mut self, str: &str) {
+        self.name = String::from(str);
+    }
+
+    pub(crate) fn get_name(&self) -> String {
+        self.name.to_owned()
     }
 
-    pub(crate) fn set_url(&self, str: &str) {
-        *self.url.borrow_mut() = String::from(str);
+    pub(crate) fn get_url(&self) -> String {
+        self.url.to_owned()
+    }
+
+    pub(crate) fn set_url(&mut self, str: &str) {
+        self.url = String::from(str);
     }
 }
 
 impl IntoMd for CIncludes {
     fn into_md(&self) -> String {
-        format!("[{}]({})", self.name.borrow(), self.url.borrow())
+        match self.url.is_empty() {
+            true => format!("`{}`", self.name),
+            false => format!("[{}]({})", self.name, self.url),
+        }
     }
 }
 
 impl TitleMd for CIncludes {
     fn create_title(&self) -> String {
-        self.name.borrow().to_owned()
+        self.name.to_owned()
     }
 }
 
 impl AnchorMd for CIncludes {
     fn create_anchor(&self) -> Option<Link> {
-        let name = self.name.borrow();
-        let url = self.url.borrow();
+        let name = &self.name;
+        let url = &self.url;
         Some(Link::new(name.as_ref(), url.as_ref(), true))
     }
 }
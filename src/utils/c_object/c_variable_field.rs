@@ -1,24 +1,86 @@
-use std::cell::RefCell;
-
 use crate::utils::IntoMd;
 
 #[derive(Clone, PartialEq, Eq)]
 pub(crate) struct CVariableField {
-    name: RefCell<String>,
-    unit: RefCell<String>,
+    name: String,
+    unit: String,
+    array: Option<String>,
+    bits: Option<String>,
+    desc: Option<String>,
 }
 
 impl CVariableField {
     pub(crate) fn new(name: &str, unit: &str) -> Self {
         CVariableField {
-            name: RefCell::new(String::from(name)),
-            unit: RefCell::new(String::from(unit)),
+            name: String::from(name),
+            unit: String::from(unit),
+            array: None,
+            bits: None,
+            desc: None,
+        }
+    }
+
+    pub(crate) fn get_name(&self) -> String {
+        self.name.to_owned()
+    }
+
+    pub(crate) fn get_unit(&self) -> String {
+        self.unit.to_owned()
+    }
+
+    // dimensions written after the name, e.g. `[16]` or `[2][3]`.
+    pub(crate) fn set_array(&mut self, array: Option<String>) {
+        self.array = array;
+    }
+
+    pub(crate) fn get_array(&self) -> Option<String> {
+        self.array.clone()
+    }
+
+    // width of bit-field, e.g. `3` for `unsigned flags : 3`.
+    pub(crate) fn set_bits(&mut self, bits: Option<String>) {
+        self.bits = bits;
+    }
+
+    pub(crate) fn get_bits(&self) -> Option<String> {
+        self.bits.clone()
+    }
+
+    pub(crate) fn append_desc(&mut self, str: &str) {
+        match self.desc.as_mut() {
+            Some(x) => {
+                x.push(' ');
+                x.push_str(str);
+            }
+            None => self.desc = Some(String::from(str)),
+        }
+    }
+
+    pub(crate) fn get_desc(&self) -> Option<String> {
+        self.desc.clone()
+    }
+
+    // full type of field, array dimensions included.
+    pub(crate) fn get_type(&self) -> String {
+        match self.array.as_ref() {
+            Some(x) => format!("{}{}", self.unit, x),
+            None => self.unit.to_owned(),
         }
     }
 }
 
 impl IntoMd for CVariableField {
     fn into_md(&self) -> String {
-        format!("{} {};", self.unit.borrow(), self.name.borrow())
+        let decl = match self.unit.contains("(*)") {
+            true => self.unit.replacen("(*)", &format!("(*{})", self.name), 1),
+            false if self.unit.ends_with('*') => format!("{}{}", self.unit, self.name),
+            false => format!("{} {}", self.unit, self.name),
+        };
+        let array = self.array.clone().unwrap_or_default();
+        let bits = match self.bits.as_ref() {
+            Some(x) => format!(" : {}", x),
+            None => String::new(),
+        };
+        format!("{}{}{};", decl, array, bits)
     }
 }
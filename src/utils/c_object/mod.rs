@@ -1,8 +1,6 @@
-use std::cell::RefCell;
-
 use c_variable_field::CVariableField;
 
-use crate::page::view::link::Link;
+use crate::{page::view::link::Link, slug::slug};
 
 use super::{AnchorMd, IntoMd, TitleMd};
 
@@ -11,12 +9,14 @@ pub(crate) mod c_variable_field;
 #[derive(Clone, Copy)]
 pub(crate) enum CObjectType {
     Struct,
+    #[allow(dead_code)]
     Union,
     Alias,
     Unknown,
 }
 
 impl CObjectType {
+    #[allow(clippy::wrong_self_convention)]
     pub(crate) fn into_str(&self) -> &str {
         match self {
             CObjectType::Struct => "struct",
@@ -29,18 +29,21 @@ impl CObjectType {
 #[derive(Clone)]
 pub(crate) struct CObject {
     obj_type: CObjectType,
-    name: RefCell<String>,
-    fields: RefCell<Vec<CVariableField>>,
-    alias: RefCell<Option<String>>,
+    name: String,
+    fields: Vec<CVariableField>,
+    alias: Option<String>,
+    // type named by alias, e.g. union for `typedef union name alias;`
+    alias_of: CObjectType,
 }
 
 impl CObject {
     pub(crate) fn new() -> Self {
         CObject {
             obj_type: CObjectType::Unknown,
-            name: RefCell::new(String::new()),
-            fields: RefCell::new(vec![]),
-            alias: RefCell::new(None),
+            name: String::new(),
+            fields: vec![],
+            alias: None,
+            alias_of: CObjectType::Unknown,
         }
     }
 
@@ -48,32 +51,58 @@ impl CObject {
         self.obj_type = obj_type;
     }
 
-    pub(crate) fn set_name(&self, name: &str) {
-        *self.name.borrow_mut() = String::from(name);
+    pub(crate) fn set_name(&mut self, name: &str) {
+        self.name = String::from(name);
+    }
+
+    pub(crate) fn get_name(&self) -> String {
+        self.name.to_owned()
+    }
+
+    pub(crate) fn get_obj_type(&self) -> CObjectType {
+        self.obj_type
+    }
+
+    pub(crate) fn get_fields(&self) -> Vec<CVariableField> {
+        self.fields.clone()
+    }
+
+    pub(crate) fn get_alias(&self) -> Option<String> {
+        self.alias.clone()
+    }
+
+    pub(crate) fn add_field(&mut self, field: CVariableField) {
+        self.fields.push(field);
     }
 
-    pub(crate) fn add_field(&self, field: CVariableField) {
-        self.fields.borrow_mut().push(field);
+    // description of field at index, from `//!` above it or `//!<` after it.
+    pub(crate) fn append_field_desc(&mut self, index: usize, str: &str) {
+        if let Some(x) = self.fields.get_mut(index) {
+            x.append_desc(str);
+        }
     }
 
-    pub(crate) fn set_alias(&self, alias: Option<String>) {
-        *self.alias.borrow_mut() = alias;
+    pub(crate) fn set_alias(&mut self, alias: Option<String>) {
+        self.alias = alias;
+    }
+
+    pub(crate) fn set_alias_of(&mut self, alias_of: CObjectType) {
+        self.alias_of = alias_of;
     }
 
     fn struct_md(&self) -> String {
-        let name = format!("struct {}", self.name.borrow());
+        let name = format!("struct {}", self.name);
         let fields = self
             .fields
-            .borrow()
             .iter()
             .map(|x| x.into_md())
             .collect::<Vec<String>>();
 
-        let fields = match fields.len() > 0 {
+        let fields = match !fields.is_empty() {
             true => fields.join("\n\t\t"),
             false => String::from("*NO FIELDS*"),
         };
-        let alias = self.alias.borrow();
+        let alias = &self.alias;
         let alias = match alias.as_ref() {
             Some(x) => format!("\n\ttypedef {} {};", name, x),
             None => String::new(),
@@ -83,19 +112,18 @@ impl CObject {
     }
 
     fn union_md(&self) -> String {
-        let name = format!("union {}", self.name.borrow());
+        let name = format!("union {}", self.name);
         let fields = self
             .fields
-            .borrow()
             .iter()
             .map(|x| x.into_md())
             .collect::<Vec<String>>();
 
-        let fields = match fields.len() > 0 {
+        let fields = match !fields.is_empty() {
             true => fields.join("\n\t\t"),
             false => String::from("*NO FIELDS*"),
         };
-        let alias = self.alias.borrow();
+        let alias = &self.alias;
         let alias = match alias.as_ref() {
             Some(x) => format!("\n\ttypedef {} {};", name, x),
             None => String::new(),
@@ -105,15 +133,16 @@ impl CObject {
     }
 
     fn alias_md(&self) -> String {
-        let name = format!("union {}", self.name.borrow());
-
-        let alias = self.alias.borrow();
-        let alias = match alias.as_ref() {
-            Some(x) => format!("\n\ttypedef {} {};", name, x),
-            None => String::new(),
+        let name = match self.alias_of.into_str() {
+            "" => self.name.clone(),
+            x => format!("{} {}", x, self.name),
         };
 
-        alias
+        let alias = &self.alias;
+        match alias.as_ref() {
+            Some(x) => format!("\ttypedef {} {};", name, x),
+            None => String::new(),
+        }
     }
 }
 
@@ -130,17 +159,17 @@ impl IntoMd for CObject {
 
 impl TitleMd for CObject {
     fn create_title(&self) -> String {
-        let alias = self.alias.borrow();
+        let alias = &self.alias;
         let title = match alias.as_ref() {
             Some(x) => x,
             None => {
                 let pre = self.obj_type.into_str();
-                let pre = if pre.len() == 0 {
+                let pre = if pre.is_empty() {
                     ""
                 } else {
-                    &format!(" {}", pre)
+                    &format!("{} ", pre)
                 };
-                &format!("{}{}", pre, self.name.borrow())
+                &format!("{}{}", pre, self.name)
             }
         };
         title.to_owned()
@@ -150,7 +179,7 @@ impl TitleMd for CObject {
 impl AnchorMd for CObject {
     fn create_anchor(&self) -> Option<Link> {
         let title = self.create_title();
-        let url = format!("#{}", title.to_lowercase().replace(" ", "-"));
+        let url = format!("#{}", slug(&title));
         Some(Link::new(&title, &url, false))
     }
 }
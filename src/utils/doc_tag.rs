@@ -0,0 +1,165 @@
+// `@tag` found in description, e.g. `@param name text` or `@return text`.
+#[derive(Clone)]
+pub(crate) struct DocTag {
+    tag: String,
+    option: Option<String>,
+    text: String,
+}
+
+impl DocTag {
+    pub(crate) fn get_tag(&self) -> &str {
+        &self.tag
+    }
+
+    // text in bracket after tag, e.g. `in` for `@param[in]`.
+    pub(crate) fn get_option(&self) -> Option<&str> {
+        self.option.as_deref()
+    }
+
+    pub(crate) fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    // first word of text, used by tag that take a name like `@param` and `@see`.
+    pub(crate) fn get_arg(&self) -> Option<&str> {
+        self.text.split_whitespace().next()
+    }
+
+    // text after first word.
+    pub(crate) fn get_rest(&self) -> &str {
+        match self.text.split_once(' ') {
+            Some((_, x)) => x.trim(),
+            None => "",
+        }
+    }
+
+    // tag as written in description, e.g. `@param[in] fd file`.
+    pub(crate) fn to_desc(&self) -> String {
+        let tag = match self.option.as_ref() {
+            Some(x) => format!("@{}[{}]", self.tag, x),
+            None => format!("@{}", self.tag),
+        };
+        match self.text.is_empty() {
+            true => tag,
+            false => format!("{} {}", tag, self.text),
+        }
+    }
+}
+
+// `//!` lines of an item with their line number in header, so warning can
+// point to line of comment instead of declaration.
+#[derive(Clone, Default)]
+pub(crate) struct CommentLines {
+    lines: Vec<(usize, String)>,
+}
+
+impl CommentLines {
+    pub(crate) fn new() -> Self {
+        CommentLines { lines: vec![] }
+    }
+
+    pub(crate) fn push(&mut self, line: usize, text: &str) {
+        self.lines.push((line, String::from(text)));
+    }
+
+    // line of first comment holding text, else `or`.
+    pub(crate) fn line_of(&self, text: &str, or: usize) -> usize {
+        self.lines
+            .iter()
+            .find(|(_, x)| x.contains(text))
+            .map(|(l, _)| *l)
+            .unwrap_or(or)
+    }
+}
+
+// heading shown for tag, e.g. `See also` for `@see`.
+pub(crate) fn tag_title(tag: &str) -> String {
+    match tag {
+        "param" => String::from("Parameters"),
+        "return" | "returns" => String::from("Returns"),
+        "see" => String::from("See also"),
+        x => {
+            let mut c = x.chars();
+            match c.next() {
+                Some(f) => f.to_uppercase().chain(c).collect(),
+                None => String::new(),
+            }
+        }
+    }
+}
+
+// `@see gx_open()` and `@see struct Obj` point to gx_open and struct Obj.
+pub(crate) fn see_target(text: &str) -> Option<String> {
+    let mut words = text.split_whitespace();
+    let first = words.next()?;
+    let target = match first {
+        "struct" | "union" | "enum" => format!("{} {}", first, words.next().unwrap_or("")),
+        _ => String::from(first),
+    };
+    Some(
+        target
+            .trim_end_matches(['.', ',', ';'])
+            .trim_end_matches("()")
+            .to_string(),
+    )
+}
+
+// `name` or `name[option]` of word starting with `@`.
+fn tag_name(word: &str) -> Option<(&str, Option<&str>)> {
+    let word = word.strip_prefix('@')?;
+    let (name, option) = match word.split_once('[') {
+        Some((n, o)) => (n, Some(o.strip_suffix(']')?)),
+        None => (word, None),
+    };
+    match !name.is_empty() && name.chars().all(|c| c.is_ascii_alphabetic()) {
+        true => Some((name, option)),
+        false => None,
+    }
+}
+
+// words of description, option of tag is part of its word even with space
+// inside, e.g. `@param[in, out]`.
+fn words(desc: &str) -> Vec<String> {
+    let mut words: Vec<String> = vec![];
+    let mut open = false;
+    for word in desc.split_whitespace() {
+        match words.last_mut() {
+            Some(last) if open => {
+                last.push(' ');
+                last.push_str(word);
+            }
+            _ => words.push(String::from(word)),
+        }
+        let last = words.last().unwrap();
+        open = last.starts_with('@') && last.contains('[') && !last.contains(']');
+    }
+    words
+}
+
+// split description into text before first tag and list of tags.
+pub(crate) fn parse_tags(desc: &str) -> (String, Vec<DocTag>) {
+    let mut text: Vec<String> = vec![];
+    let mut tags: Vec<DocTag> = vec![];
+
+    for word in words(desc) {
+        match tag_name(&word) {
+            Some((x, option)) => {
+                tags.push(DocTag {
+                    tag: String::from(x),
+                    option: option.map(String::from),
+                    text: String::new(),
+                });
+            }
+            _ => match tags.last_mut() {
+                Some(t) => {
+                    if !t.text.is_empty() {
+                        t.text.push(' ');
+                    }
+                    t.text.push_str(&word);
+                }
+                None => text.push(word),
+            },
+        }
+    }
+    (text.join(" "), tags)
+}
@@ -1,14 +1,15 @@
-use std::cell::RefCell;
-
 use crate::page::view::link::Link;
 
 pub(crate) mod c_function;
 pub(crate) mod c_function_param;
+pub(crate) mod c_impl;
 pub(crate) mod c_includes;
 
 pub(crate) mod c_object;
+pub(crate) mod doc_tag;
 
 pub(crate) trait IntoMd {
+    #[allow(clippy::wrong_self_convention)]
     fn into_md(&self) -> String;
 }
 
@@ -23,23 +24,25 @@ pub(crate) trait AnchorMd {
 // when start with ///!
 #[derive(Clone)]
 pub(crate) struct CommentMain {
-    content: RefCell<Vec<String>>,
+    content: Vec<String>,
 }
 
 impl CommentMain {
     pub(crate) fn new() -> Self {
-        CommentMain {
-            content: RefCell::new(vec![]),
-        }
+        CommentMain { content: vec![] }
+    }
+
+    pub(crate) fn get_lines(&self) -> Vec<String> {
+        self.content.clone()
     }
 
-    pub(crate) fn append(&self, str: &str) {
-        self.content.borrow_mut().push(String::from(str));
+    pub(crate) fn append(&mut self, str: &str) {
+        self.content.push(String::from(str));
     }
 }
 
 impl IntoMd for CommentMain {
     fn into_md(&self) -> String {
-        self.content.borrow().join("\n")
+        self.content.join("\n")
     }
 }
@@ -0,0 +1,208 @@
+use std::{
+    collections::BTreeMap,
+    fs::metadata,
+    panic::{catch_unwind, AssertUnwindSafe},
+    thread::sleep,
+    time::{Duration, SystemTime},
+};
+
+use crate::{config::Config, error::Error, file_reader::file_list};
+
+// time between two scan of source directory.
+const POLL: Duration = Duration::from_millis(500);
+
+// modification time and size of every watched file.
+type Snapshot = BTreeMap<String, (Option<SystemTime>, u64)>;
+
+fn snapshot(cfg: &Config) -> Snapshot {
+    // directory being replaced is listed again on next poll
+    let mut files: Vec<String> = vec![];
+    let _ = file_list(&mut files, &cfg.src, ".h");
+    if cfg.merge_impl {
+        let _ = file_list(&mut files, &cfg.src, ".c");
+    }
+    if let Some(x) = cfg.template_dir.as_ref() {
+        let _ = file_list(&mut files, x, ".tpl");
+    }
+    files
+        .into_iter()
+        .filter_map(|f| {
+            let meta = metadata(&f).ok()?;
+            Some((f, (meta.modified().ok(), meta.len())))
+        })
+        .collect()
+}
+
+// list of changed file with kind of change, empty when nothing changed.
+fn changes(old: &Snapshot, new: &Snapshot) -> Vec<String> {
+    let mut changes = vec![];
+    for (f, stamp) in new {
+        match old.get(f) {
+            None => changes.push(format!("added {}", f)),
+            Some(x) if x != stamp => changes.push(format!("modified {}", f)),
+            Some(_) => {}
+        }
+    }
+    for f in old.keys().filter(|f| !new.contains_key(*f)) {
+        changes.push(format!("removed {}", f));
+    }
+    changes
+}
+
+// run generate, failure or panic is reported and watching go on.
+// header saved half-edited must not stop the watch.
+fn rebuild<F>(generate: &F) -> bool
+where
+    F: Fn() -> Result<(), Error>,
+{
+    match catch_unwind(AssertUnwindSafe(generate)) {
+        Ok(Ok(())) => true,
+        Ok(Err(e)) => {
+            eprintln!("{}", e);
+            eprintln!("::> Warning: generation is failed, waiting for next change.");
+            false
+        }
+        Err(_) => {
+            eprintln!("::> Warning: generation is aborted, waiting for next change.");
+            false
+        }
+    }
+}
+
+// poll every `poll` until stop is true and run generate after every change.
+fn watch_until<F, S>(cfg: &Config, poll: Duration, generate: F, mut stop: S)
+where
+    F: Fn() -> Result<(), Error>,
+    S: FnMut() -> bool,
+{
+    let mut old = snapshot(cfg);
+    while !stop() {
+        sleep(poll);
+        let new = snapshot(cfg);
+        let changes = changes(&old, &new);
+        if changes.is_empty() {
+            continue;
+        }
+        println!(":> Change detected: {}.", changes.join(", "));
+        rebuild(&generate);
+        old = new;
+    }
+}
+
+// poll source directory and run generate after every change, never return.
+// pages not affected by the change are kept by the cache.
+pub(crate) fn watch<F>(cfg: &Config, generate: F)
+where
+    F: Fn() -> Result<(), Error>,
+{
+    println!(":> Watching {} for changes, press Ctrl+C to stop.", cfg.src);
+    watch_until(cfg, POLL, generate, || false);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::Cell,
+        env::temp_dir,
+        fs::{create_dir_all, remove_dir_all, write},
+        path::PathBuf,
+    };
+
+    use super::*;
+
+    fn stamp(secs: u64, len: u64) -> (Option<SystemTime>, u64) {
+        (
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs)),
+            len,
+        )
+    }
+
+    fn snap(files: &[(&str, u64, u64)]) -> Snapshot {
+        files
+            .iter()
+            .map(|(f, secs, len)| (f.to_string(), stamp(*secs, *len)))
+            .collect()
+    }
+
+    #[test]
+    fn added_modified_removed() {
+        let old = snap(&[("a.h", 1, 10), ("b.h", 1, 10), ("c.h", 1, 10)]);
+        let new = snap(&[("a.h", 1, 10), ("b.h", 2, 10), ("d.h", 1, 10)]);
+        assert_eq!(
+            changes(&old, &new),
+            vec!["modified b.h", "added d.h", "removed c.h"]
+        );
+        // same time but other size is change too
+        let new = snap(&[("a.h", 1, 10), ("b.h", 1, 12), ("c.h", 1, 10)]);
+        assert_eq!(changes(&old, &new), vec!["modified b.h"]);
+        assert!(changes(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn snapshot_of_sources() {
+        let dir: PathBuf = temp_dir().join(format!("gx_md_watch_{}", std::process::id()));
+        let _ = remove_dir_all(&dir);
+        create_dir_all(&dir).unwrap();
+        let src = dir.to_str().unwrap().to_string();
+        write(dir.join("t.h"), "int a;").unwrap();
+        write(dir.join("t.c"), "int a;").unwrap();
+        write(dir.join("notes.txt"), "-").unwrap();
+
+        let config = |extra: &[&str]| {
+            let mut args = vec!["gx_md", "-src", &src, "-o", "out", "-h", "t.h"];
+            args.extend(extra);
+            Config::from_args(args.iter().map(|x| x.to_string()).collect()).unwrap()
+        };
+        let names = |s: Snapshot| {
+            s.keys()
+                .map(|f| f.rsplit('/').next().unwrap().to_string())
+                .collect::<Vec<String>>()
+        };
+        let headers = names(snapshot(&config(&[])));
+        let with_impl = names(snapshot(&config(&["-impl"])));
+        let _ = remove_dir_all(&dir);
+
+        assert_eq!(headers, vec!["t.h"]);
+        assert_eq!(with_impl, vec!["t.c", "t.h"]);
+    }
+
+    #[test]
+    fn failed_rebuild_keep_watching() {
+        let dir: PathBuf = temp_dir().join(format!("gx_md_rebuild_{}", std::process::id()));
+        let _ = remove_dir_all(&dir);
+        create_dir_all(&dir).unwrap();
+        let header = dir.join("t.h");
+        write(&header, "int a").unwrap();
+        let args = [
+            "gx_md",
+            "-src",
+            dir.to_str().unwrap(),
+            "-o",
+            "out",
+            "-h",
+            "t.h",
+        ];
+        let cfg = Config::from_args(args.iter().map(|x| x.to_string()).collect()).unwrap();
+
+        let calls = Cell::new(0);
+        let generate = || {
+            calls.set(calls.get() + 1);
+            match calls.get() {
+                1 => panic!("half-typed declaration"),
+                2 => Err(Error::failed(2, "Reading file: t.h", "not found")),
+                _ => Ok(()),
+            }
+        };
+        // every poll see the header edited again
+        let mut edits = 0;
+        let stop = || {
+            edits += 1;
+            write(&header, "int a;".repeat(edits)).unwrap();
+            calls.get() == 3 || edits > 50
+        };
+        watch_until(&cfg, Duration::from_millis(1), generate, stop);
+        let _ = remove_dir_all(&dir);
+
+        assert_eq!(calls.get(), 3);
+    }
+}
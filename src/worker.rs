@@ -0,0 +1,145 @@
+use std::{
+    panic::resume_unwind,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+// number of worker when `-j` is not given.
+pub(crate) fn default_jobs() -> usize {
+    thread::available_parallelism()
+        .map(|x| x.get())
+        .unwrap_or(1)
+}
+
+// run job on every item using `jobs` worker thread.
+// result is in the same order as items whatever order worker finish.
+// panic of a job is raised again in the caller with its own message.
+pub(crate) fn parallel_map<T, R, F>(items: &[T], jobs: usize, job: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let jobs = jobs.clamp(1, items.len().max(1));
+    if jobs == 1 {
+        return items.iter().map(job).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut done: Vec<(usize, R)> = thread::scope(|s| {
+        let workers = (0..jobs)
+            .map(|_| {
+                s.spawn(|| {
+                    let mut done = vec![];
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        match items.get(i) {
+                            Some(x) => done.push((i, job(x))),
+                            None => return done,
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut done = vec![];
+        let mut failed = None;
+        for w in workers {
+            match w.join() {
+                Ok(x) => done.extend(x),
+                Err(e) => failed = failed.or(Some(e)),
+            }
+        }
+        if let Some(e) = failed {
+            resume_unwind(e);
+        }
+        done
+    });
+    done.sort_by_key(|(i, _)| *i);
+    done.into_iter().map(|(_, r)| r).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        panic::{catch_unwind, AssertUnwindSafe},
+        sync::Mutex,
+        thread::ThreadId,
+        time::Duration,
+    };
+
+    use super::*;
+
+    #[test]
+    fn keep_order() {
+        let items = (0..20).collect::<Vec<u64>>();
+        // later item finish first
+        let done = parallel_map(&items, 4, |x| {
+            thread::sleep(Duration::from_millis(20 - x));
+            x * 2
+        });
+        assert_eq!(done, (0..20).map(|x| x * 2).collect::<Vec<u64>>());
+    }
+
+    #[test]
+    fn one_job_in_caller() {
+        let caller = thread::current().id();
+        let threads = Mutex::new(vec![]);
+        let done = parallel_map(&[1, 2, 3], 1, |x| {
+            threads.lock().unwrap().push(thread::current().id());
+            x + 1
+        });
+        assert_eq!(done, vec![2, 3, 4]);
+        assert!(threads.lock().unwrap().iter().all(|t| *t == caller));
+    }
+
+    #[test]
+    fn more_jobs_than_items() {
+        let caller = thread::current().id();
+        let threads: Mutex<Vec<ThreadId>> = Mutex::new(vec![]);
+        let done = parallel_map(&["a", "b"], 16, |x| {
+            threads.lock().unwrap().push(thread::current().id());
+            x.to_uppercase()
+        });
+        assert_eq!(done, vec!["A", "B"]);
+        // every job still run once, in worker thread
+        let threads = threads.lock().unwrap();
+        assert_eq!(threads.len(), 2);
+        assert!(threads.iter().all(|t| *t != caller));
+    }
+
+    #[test]
+    fn empty_items() {
+        let done = parallel_map(&[] as &[u8], 4, |x| *x);
+        assert!(done.is_empty());
+        let done = parallel_map(&[] as &[u8], 0, |x| *x);
+        assert!(done.is_empty());
+    }
+
+    #[test]
+    fn error_of_job() {
+        let done = parallel_map(&[1, 2, 3, 4], 2, |x| match x % 2 {
+            0 => Err(format!("{} is even", x)),
+            _ => Ok(*x),
+        })
+        .into_iter()
+        .collect::<Result<Vec<i32>, String>>();
+        assert_eq!(done, Err(String::from("2 is even")));
+    }
+
+    #[test]
+    fn panic_of_job() {
+        let items = (0..8).collect::<Vec<i32>>();
+        let done = catch_unwind(AssertUnwindSafe(|| {
+            parallel_map(&items, 3, |x| match x {
+                5 => panic!("job {} is broken", x),
+                _ => *x,
+            })
+        }));
+        let e = done.err().unwrap();
+        let msg = e
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| e.downcast_ref::<&str>().map(|x| x.to_string()));
+        assert_eq!(msg.as_deref(), Some("job 5 is broken"));
+    }
+}
@@ -0,0 +1,39 @@
+///! Header with grouped declarations.
+
+#ifndef _GROUPS_
+#define _GROUPS_
+
+//! not grouped function
+int plain(void);
+
+//! @group Buffer
+//! Functions to manage buffer.
+
+//! open buffer
+int buf_open(int size);
+
+//! close buffer
+//! @param ident buffer id
+//! @return nothing
+//! @see buf_open
+//! @see gx_missing()
+void buf_close(int id);
+//! @endgroup
+
+///! ## Helpers
+
+//! helper struct
+struct Helper {
+  int x;
+};
+
+//! help me, see [`buf_open`], [`struct Obj`] and [`nope`].
+int helper(struct Helper *h);
+
+//! @private
+//! internal counter
+int _gx_internal_count(void);
+
+int _gx_internal_reset(void);
+
+#endif
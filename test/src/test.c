@@ -0,0 +1,15 @@
+#include "test.h"
+
+//! Compute something from `a` and the fields of `obj`.
+//! Returns the sum of all fields.
+int test_function(int a, Obj *o) {
+  return a + o->a + o->b + o->c;
+}
+
+//! Always returns zero.
+double test_fun(void)
+{
+  return 0.0;
+}
+
+static int helper(int x) { return x; }